@@ -0,0 +1,227 @@
+/*
+Copyright (C) 2025 Amari Calipso
+
+This file is part of !pong.
+
+!pong is free software: you can redistribute it and/or modify
+it under the terms of the GNU Affero General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+!pong is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Affero General Public License for more details.
+
+You should have received a copy of the GNU Affero General Public License
+along with !pong.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use raylib::math::Vector2;
+
+use crate::{input::{Action, ActionState, Actions}, input_source::{GameView, InputFrame, InputSource}, obstacle::{AnyObstacle, Obstacle, Shape}, pad::Pad, BOT_FLY_DURATIONS, BOT_FLY_OFFSETS, BOT_HORIZON, BOT_OBSTACLE_MARGIN, BOT_PAD_MARGIN, BOT_SPRINT_DURATIONS, GRAVITY, INTERNAL_RESOLUTION, JUMP_VELOCITY, MINE_TRIGGER_RADIUS, PLAYER_SIZE, SPRINT_COOLDOWN, SPRINT_MAX_VALUE, SPRINT_USE_DELTA, SPRINT_VELOCITY};
+
+#[derive(Debug, Clone, Copy)]
+enum Plan {
+    /// Jumps whenever the player falls below the height that lines it up with the next pad,
+    /// moved by `offset` for the first `duration` frames
+    Fly { offset: f32, duration: u32 },
+    /// Sprints for `duration` frames, then flies normally
+    Sprint { duration: u32 }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Outcome {
+    /// Frames the player survives for, up to `BOT_HORIZON`
+    survived: u32,
+    /// Whether the plan jumps on the first frame
+    jumps: bool
+}
+
+fn overlaps_pad(pad: &Pad, y: f32) -> bool {
+    let top = pad.destination();
+    y + PLAYER_SIZE > top + BOT_PAD_MARGIN && y < top + pad.size().y - BOT_PAD_MARGIN
+}
+
+fn overlaps(pos: Vector2, size: Vector2, other_pos: Vector2, other_size: Vector2) -> bool {
+    pos.x - BOT_OBSTACLE_MARGIN < other_pos.x + other_size.x && other_pos.x < pos.x + size.x + BOT_OBSTACLE_MARGIN &&
+    pos.y - BOT_OBSTACLE_MARGIN < other_pos.y + other_size.y && other_pos.y < pos.y + size.y + BOT_OBSTACLE_MARGIN
+}
+
+/// Predicts what happens to the player if it follows `plan`, assuming obstacles keep moving in a straight line
+fn simulate(game: &GameView, plan: Plan) -> Outcome {
+    let mut pos = game.player.pos;
+    let mut velocity = game.player.velocity();
+    let mut sprinting = matches!(plan, Plan::Sprint { .. });
+    let mut jumps = false;
+
+    if sprinting && !game.player.sprinting {
+        velocity.y = 0.0;
+    }
+
+    // the pad that was just hit moves somewhere random, so the player is assumed safe once it gets back to it
+    let mut bounced = false;
+
+    for t in 0 .. BOT_HORIZON {
+        let target = if velocity.x > 0.0 { game.right_pad } else { game.left_pad };
+
+        match plan {
+            Plan::Sprint { duration } if sprinting && t >= duration => sprinting = false,
+            _ => ()
+        }
+
+        if sprinting {
+            pos.x += SPRINT_VELOCITY.copysign(velocity.x);
+        } else {
+            let offset = match plan {
+                Plan::Fly { offset, duration } if t < duration => offset,
+                _ => 0.0
+            };
+
+            let threshold = target.destination() + target.size().y - PLAYER_SIZE - BOT_PAD_MARGIN * 2.0 + offset;
+            if pos.y >= threshold {
+                velocity.y = -JUMP_VELOCITY;
+                jumps |= t == 0;
+            }
+
+            velocity += GRAVITY;
+            pos += velocity;
+        }
+
+        if pos.y <= 0.0 || pos.y + PLAYER_SIZE >= INTERNAL_RESOLUTION.y {
+            return Outcome { survived: t, jumps };
+        }
+
+        let reached = {
+            if velocity.x > 0.0 {
+                pos.x + PLAYER_SIZE >= target.pos.x
+            } else {
+                pos.x <= target.pos.x + target.size().x
+            }
+        };
+
+        if reached {
+            if bounced {
+                break;
+            }
+
+            if !overlaps_pad(target, pos.y) {
+                return Outcome { survived: t, jumps };
+            }
+
+            bounced = true;
+            velocity.x = -velocity.x;
+        }
+
+        let time = (t + 1) as f32 * game.obstacle_time_scale;
+        for obstacle in game.obstacles {
+            // armed mines go off as soon as the player gets close, not only when touched
+            if let AnyObstacle::Mine(mine) = obstacle && mine.is_armed() {
+                let area = Shape::Circle { center: mine.center(), radius: MINE_TRIGGER_RADIUS };
+                if area.overlaps_rect(pos, Vector2 { x: PLAYER_SIZE, y: PLAYER_SIZE }) {
+                    return Outcome { survived: t, jumps };
+                }
+
+                continue;
+            }
+
+            let collides = match obstacle {
+                // lasers start and stop being dangerous within the horizon
+                AnyObstacle::Laser(laser) => laser.is_firing_after(time),
+                _ => obstacle.can_collide()
+            };
+
+            if !collides {
+                continue;
+            }
+
+            if overlaps(pos, Vector2 { x: PLAYER_SIZE, y: PLAYER_SIZE }, obstacle.pos() + obstacle.velocity() * time, obstacle.size()) {
+                return Outcome { survived: t, jumps };
+            }
+        }
+    }
+
+    Outcome { survived: BOT_HORIZON, jumps }
+}
+
+/// Plays the game by predicting the player's trajectory under a few plans and following the safest one
+#[derive(Debug, Clone, Default)]
+pub struct Bot {
+    jump_held: bool,
+    sprint_held: bool
+}
+
+impl Bot {
+    /// Plans to try, most preferred first
+    fn plans(game: &GameView) -> Vec<Plan> {
+        let mut plans = Vec::new();
+
+        let sprint_frames = (game.sprint_amount / SPRINT_USE_DELTA) as u32;
+        let can_sprint = game.player.sprinting || (game.sprint_amount > 0.0 && game.sprint_cooldown >= SPRINT_COOLDOWN);
+        let sprints = BOT_SPRINT_DURATIONS.into_iter()
+            .filter(|duration| *duration <= sprint_frames)
+            .map(|duration| Plan::Sprint { duration });
+
+        // sprinting scores faster, so a full charge gets used as soon as it's safe to
+        let charged = game.player.sprinting || game.sprint_amount >= SPRINT_MAX_VALUE;
+        if can_sprint && charged {
+            plans.extend(sprints.clone());
+        }
+
+        for offset in BOT_FLY_OFFSETS {
+            for duration in BOT_FLY_DURATIONS {
+                plans.push(Plan::Fly { offset, duration });
+
+                if offset == 0.0 {
+                    break;
+                }
+            }
+        }
+
+        // otherwise, sprint is kept as a way out
+        if can_sprint && !charged {
+            plans.extend(sprints);
+        }
+
+        plans
+    }
+
+    fn decide(game: &GameView) -> (bool, bool) {
+        if !game.player.playing {
+            return (true, false);
+        }
+
+        let mut best: Option<(Plan, Outcome)> = None;
+        for plan in Self::plans(game) {
+            let outcome = simulate(game, plan);
+
+            if best.is_none_or(|(_, best)| outcome.survived > best.survived) {
+                best = Some((plan, outcome));
+            }
+
+            if outcome.survived == BOT_HORIZON {
+                break;
+            }
+        }
+
+        match best {
+            Some((Plan::Sprint { .. }, _)) => (false, true),
+            Some((Plan::Fly { .. }, outcome)) => (outcome.jumps, false),
+            None => (false, false)
+        }
+    }
+}
+
+impl InputSource for Bot {
+    fn poll(&mut self, frame: &InputFrame) -> Actions {
+        let (jump, sprint) = Self::decide(&frame.game);
+
+        let mut actions = Actions::default();
+        actions.set(Action::Jump, ActionState::transition(self.jump_held, jump && !self.jump_held));
+        actions.set(Action::Sprint, ActionState::transition(self.sprint_held, sprint));
+
+        self.jump_held = jump && !self.jump_held;
+        self.sprint_held = sprint;
+        actions
+    }
+}
\ No newline at end of file
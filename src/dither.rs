@@ -19,6 +19,8 @@ along with !pong.  If not, see <http://www.gnu.org/licenses/>.
 
 use raylib::texture::Image;
 
+use crate::settings::{DitherMode, Theme};
+
 const CUTOFF: u8 = 3;
 
 const THRESHOLDS: [[u8; 8]; 8] = {
@@ -47,21 +49,45 @@ const THRESHOLDS: [[u8; 8]; 8] = {
     t
 };
 
-pub fn apply(image: &mut Image) {
+fn lerp(from: u8, to: u8, amount: u8) -> u8 {
+    ((from as u16 * (255 - amount as u16) + to as u16 * amount as u16) / 255) as u8
+}
+
+/// Dithers the image using the given mode, then maps it onto the theme's colors
+pub fn apply(image: &mut Image, mode: DitherMode, theme: Theme) {
+    if mode == DitherMode::Off && theme == Theme::Classic {
+        return;
+    }
+
     let width = image.width as usize;
     let height = image.height as usize;
     let size = width * height * 4;
     let data = unsafe { std::slice::from_raw_parts_mut(image.data as *mut u8, size) };
 
+    let fg = theme.fg();
+    let bg = theme.bg();
+
     for y in 0 .. image.height as usize {
         for x in 0 .. width {
-            let t = THRESHOLDS[y % THRESHOLDS.len()][x % THRESHOLDS[0].len()];
-
             let i = 4 * (y * width + x);
-            data[i + 0] = (data[i + 0] > t) as u8 * 255;
-            data[i + 1] = (data[i + 1] > t) as u8 * 255;
-            data[i + 2] = (data[i + 2] > t) as u8 * 255;
-            // don't edit alpha
+
+            if let Some(matrix_size) = mode.matrix_size() {
+                // smaller matrices are just the top left corner of the bigger one
+                let t = THRESHOLDS[y % matrix_size][x % matrix_size];
+
+                data[i + 0] = (data[i + 0] > t) as u8 * 255;
+                data[i + 1] = (data[i + 1] > t) as u8 * 255;
+                data[i + 2] = (data[i + 2] > t) as u8 * 255;
+                // don't edit alpha
+            }
+
+            // only grays are remapped, so rainbow effects keep their colors
+            if data[i] == data[i + 1] && data[i + 1] == data[i + 2] {
+                let value = data[i];
+                data[i + 0] = lerp(bg.r, fg.r, value);
+                data[i + 1] = lerp(bg.g, fg.g, value);
+                data[i + 2] = lerp(bg.b, fg.b, value);
+            }
         }
     }
 }
\ No newline at end of file
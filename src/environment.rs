@@ -0,0 +1,503 @@
+/*
+Copyright (C) 2025 Amari Calipso
+
+This file is part of !pong.
+
+!pong is free software: you can redistribute it and/or modify
+it under the terms of the GNU Affero General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+!pong is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Affero General Public License for more details.
+
+You should have received a copy of the GNU Affero General Public License
+along with !pong.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use std::{collections::HashMap, io::{self, BufRead, Write}, iter::Peekable, str::Chars};
+
+use raylib::math::Vector2;
+
+use crate::{input::{Action, ActionState, Actions}, obstacle::{AnyObstacle, Obstacle}, player::DeathCause, world::{World, WorldEvent}, FrameInfo, ENV_DEATH_REWARD, ENV_OBSERVED_OBSTACLES, ENV_SURVIVAL_REWARD, PLAYER_SIZE, SIMULATION_MAX_FRAMES, SPRINT_MAX_VALUE};
+
+/// Actions held by the agent during a step. Like with a real button, jump has to be released before it can be pressed again
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EnvAction {
+    pub jump: bool,
+    pub sprint: bool
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ObstacleObservation {
+    pub kind: &'static str,
+    /// Position relative to the player
+    pub pos: Vector2,
+    pub velocity: Vector2,
+    pub size: Vector2
+}
+
+/// What the agent sees of the game. Positions and velocities are in internal resolution pixels and frames
+#[derive(Debug, Clone, PartialEq)]
+pub struct Observation {
+    pub player_pos: Vector2,
+    pub player_velocity: Vector2,
+    pub sprinting: bool,
+    pub left_pad_y: f32,
+    pub right_pad_y: f32,
+    pub pad_height: f32,
+    /// Between 0 and 1
+    pub sprint_charge: f32,
+    /// Up to `ENV_OBSERVED_OBSTACLES` collidable obstacles, nearest first
+    pub obstacles: Vec<ObstacleObservation>
+}
+
+impl Observation {
+    fn new(world: &World) -> Self {
+        let center = world.player.pos + Vector2 { x: PLAYER_SIZE / 2.0, y: PLAYER_SIZE / 2.0 };
+
+        let mut obstacles: Vec<ObstacleObservation> = world.obstacles.iter()
+            .filter(|x| x.can_collide())
+            .map(|x| ObstacleObservation {
+                kind: match x {
+                    AnyObstacle::Rock(_) => "rock",
+                    AnyObstacle::Asteroid(_) => "asteroid",
+                    AnyObstacle::Barrier(_) => "barrier",
+                    AnyObstacle::Rocket(_) => "rocket",
+                    AnyObstacle::Missile(_) => "missile",
+                    AnyObstacle::Laser(_) => "laser",
+                    AnyObstacle::GravityWell(_) => "gravity_well",
+                    AnyObstacle::Mine(_) => "mine",
+                    AnyObstacle::ObstacleExplosion(_) => "explosion"
+                },
+                pos: x.pos() - world.player.pos,
+                velocity: x.velocity(),
+                size: x.size()
+            })
+            .collect();
+
+        let distance = |x: &ObstacleObservation| (world.player.pos + x.pos + x.size / 2.0).distance_to(center);
+        obstacles.sort_by(|a, b| distance(a).total_cmp(&distance(b)));
+        obstacles.truncate(ENV_OBSERVED_OBSTACLES);
+
+        Self {
+            player_pos: world.player.pos,
+            player_velocity: world.player.velocity(),
+            sprinting: world.player.sprinting,
+            left_pad_y: world.left_pad.pos.y,
+            right_pad_y: world.right_pad.pos.y,
+            pad_height: world.left_pad.size().y,
+            sprint_charge: world.sprint_amount / SPRINT_MAX_VALUE,
+            obstacles
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        let obstacles: Vec<String> = self.obstacles.iter()
+            .map(|x| format!(
+                "{{\"kind\": \"{}\", \"x\": {}, \"y\": {}, \"vx\": {}, \"vy\": {}, \"width\": {}, \"height\": {}}}",
+                x.kind, x.pos.x, x.pos.y, x.velocity.x, x.velocity.y, x.size.x, x.size.y
+            ))
+            .collect();
+
+        format!(
+            concat!(
+                "{{\"player_x\": {}, \"player_y\": {}, \"player_vx\": {}, \"player_vy\": {}, \"sprinting\": {}, ",
+                "\"left_pad_y\": {}, \"right_pad_y\": {}, \"pad_height\": {}, \"sprint_charge\": {}, \"obstacles\": [{}]}}"
+            ),
+            self.player_pos.x, self.player_pos.y, self.player_velocity.x, self.player_velocity.y, self.sprinting,
+            self.left_pad_y, self.right_pad_y, self.pad_height, self.sprint_charge, obstacles.join(", ")
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StepResult {
+    pub observation: Observation,
+    pub reward: f32,
+    pub done: bool
+}
+
+/// Gym-style wrapper over the simulation: every step advances the game by one frame at the reference framerate.
+/// Points scored are rewarded, as is every frame survived, while dying is penalized
+#[derive(Debug, Default)]
+pub struct Environment {
+    world: Option<World>,
+    held: EnvAction,
+    frame: u64,
+    score: u64,
+    death_cause: Option<DeathCause>
+}
+
+impl Environment {
+    pub fn reset(&mut self, seed: u64) -> Observation {
+        let world = self.world.insert(World::new(seed));
+        self.held = EnvAction::default();
+        self.frame = 0;
+        self.score = 0;
+        self.death_cause = None;
+        Observation::new(world)
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.death_cause.is_some() || self.frame >= SIMULATION_MAX_FRAMES
+    }
+
+    pub fn score(&self) -> u64 {
+        self.score
+    }
+
+    pub fn death_cause(&self) -> Option<DeathCause> {
+        self.death_cause
+    }
+
+    /// Returns `None` if there's no episode going, meaning `reset` has to be called first
+    pub fn step(&mut self, action: EnvAction) -> Option<StepResult> {
+        if self.is_done() {
+            return None;
+        }
+
+        let world = self.world.as_mut()?;
+
+        let mut actions = Actions::default();
+        actions.set(Action::Jump, ActionState::transition(self.held.jump, action.jump));
+        actions.set(Action::Sprint, ActionState::transition(self.held.sprint, action.sprint));
+        self.held = action;
+
+        world.apply(&actions);
+        world.step(FrameInfo::new(1.0, true));
+        self.frame += 1;
+
+        let mut score = world.player.count;
+        let mut reward = ENV_SURVIVAL_REWARD;
+
+        for event in world.events.drain(..) {
+            if let WorldEvent::Death { cause, score: final_score } = event {
+                self.death_cause = Some(cause);
+                score = final_score;
+                reward += ENV_DEATH_REWARD;
+            }
+        }
+
+        reward += score.saturating_sub(self.score) as f32;
+        self.score = score;
+
+        Some(StepResult {
+            observation: Observation::new(world),
+            reward,
+            done: self.is_done()
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Null,
+    Bool(bool),
+    /// Kept as written, so that integers too big for a `f64` (like seeds) aren't rounded
+    Number(String),
+    String(String)
+}
+
+fn skip_whitespace(chars: &mut Peekable<Chars>) {
+    while chars.next_if(|c| c.is_whitespace()).is_some() {}
+}
+
+/// Reads the 4 hex digits of a `\u` escape
+fn parse_hex4(chars: &mut Peekable<Chars>) -> Result<u32, String> {
+    let mut code = 0;
+    for _ in 0 .. 4 {
+        let digit = chars.next().and_then(|c| c.to_digit(16)).ok_or("invalid unicode escape")?;
+        code = code * 16 + digit;
+    }
+
+    Ok(code)
+}
+
+/// Decodes the escape sequence following a backslash
+fn parse_escape(chars: &mut Peekable<Chars>) -> Result<char, String> {
+    match chars.next() {
+        Some(c @ ('"' | '\\' | '/')) => Ok(c),
+        Some('b') => Ok('\u{8}'),
+        Some('f') => Ok('\u{c}'),
+        Some('n') => Ok('\n'),
+        Some('r') => Ok('\r'),
+        Some('t') => Ok('\t'),
+        Some('u') => {
+            let mut code = parse_hex4(chars)?;
+
+            // characters outside the basic multilingual plane are escaped as a surrogate pair
+            if (0xD800 .. 0xDC00).contains(&code) {
+                if chars.next() != Some('\\') || chars.next() != Some('u') {
+                    return Err("unpaired surrogate in unicode escape".to_string());
+                }
+
+                let low = parse_hex4(chars)?;
+                if !(0xDC00 .. 0xE000).contains(&low) {
+                    return Err("unpaired surrogate in unicode escape".to_string());
+                }
+
+                code = 0x10000 + ((code - 0xD800) << 10) + (low - 0xDC00);
+            }
+
+            char::from_u32(code).ok_or_else(|| "invalid unicode escape".to_string())
+        }
+        Some(c) => Err(format!("invalid escape: \\{}", c)),
+        None => Err("unterminated string".to_string())
+    }
+}
+
+fn parse_string(chars: &mut Peekable<Chars>) -> Result<String, String> {
+    if chars.next() != Some('"') {
+        return Err("expected string".to_string());
+    }
+
+    let mut out = String::new();
+    loop {
+        match chars.next() {
+            Some('"') => return Ok(out),
+            Some('\\') => out.push(parse_escape(chars)?),
+            Some(c) => out.push(c),
+            None => return Err("unterminated string".to_string())
+        }
+    }
+}
+
+fn parse_value(chars: &mut Peekable<Chars>) -> Result<Value, String> {
+    if chars.peek() == Some(&'"') {
+        return parse_string(chars).map(Value::String);
+    }
+
+    let mut token = String::new();
+    while let Some(c) = chars.next_if(|c| c.is_alphanumeric() || matches!(c, '-' | '+' | '.')) {
+        token.push(c);
+    }
+
+    match token.as_str() {
+        "null" => Ok(Value::Null),
+        "true" => Ok(Value::Bool(true)),
+        "false" => Ok(Value::Bool(false)),
+        _ => match token.parse::<f64>() {
+            Ok(_) => Ok(Value::Number(token)),
+            Err(_) => Err(format!("invalid value: {}", token))
+        }
+    }
+}
+
+/// Parses a JSON object whose values are all strings, numbers, booleans or null
+fn parse_object(line: &str) -> Result<HashMap<String, Value>, String> {
+    let mut chars = line.chars().peekable();
+    let mut object = HashMap::new();
+
+    skip_whitespace(&mut chars);
+    if chars.next() != Some('{') {
+        return Err("expected object".to_string());
+    }
+
+    skip_whitespace(&mut chars);
+    if chars.next_if_eq(&'}').is_none() {
+        loop {
+            skip_whitespace(&mut chars);
+            let key = parse_string(&mut chars)?;
+
+            skip_whitespace(&mut chars);
+            if chars.next() != Some(':') {
+                return Err("expected ':'".to_string());
+            }
+
+            skip_whitespace(&mut chars);
+            object.insert(key, parse_value(&mut chars)?);
+
+            skip_whitespace(&mut chars);
+            match chars.next() {
+                Some(',') => (),
+                Some('}') => break,
+                _ => return Err("expected ',' or '}'".to_string())
+            }
+        }
+    }
+
+    skip_whitespace(&mut chars);
+    if chars.next().is_some() {
+        return Err("trailing characters after object".to_string());
+    }
+
+    Ok(object)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Request {
+    /// Starts a new episode. A random seed is used if none is given
+    Reset { seed: Option<u64> },
+    /// Holds the given actions for `frames` frames, or until the episode ends
+    Step { action: EnvAction, frames: u32 },
+    Close
+}
+
+impl Request {
+    fn parse(line: &str) -> Result<Self, String> {
+        let object = parse_object(line)?;
+
+        let bool_field = |name: &str| match object.get(name) {
+            None | Some(Value::Null) => Ok(false),
+            Some(Value::Bool(x)) => Ok(*x),
+            _ => Err(format!("\"{}\" must be a boolean", name))
+        };
+
+        let int_field = |name: &str| match object.get(name) {
+            None | Some(Value::Null) => Ok(None),
+            Some(Value::Number(x)) => x.parse::<u64>().map(Some).map_err(|_| format!("\"{}\" must be a non-negative integer", name)),
+            _ => Err(format!("\"{}\" must be a non-negative integer", name))
+        };
+
+        match object.get("cmd") {
+            Some(Value::String(cmd)) if cmd == "reset" => Ok(Request::Reset { seed: int_field("seed")? }),
+            Some(Value::String(cmd)) if cmd == "step" => Ok(Request::Step {
+                action: EnvAction {
+                    jump: bool_field("jump")?,
+                    sprint: bool_field("sprint")?
+                },
+                frames: int_field("frames")?.unwrap_or(1).clamp(1, u32::MAX as u64) as u32
+            }),
+            Some(Value::String(cmd)) if cmd == "close" => Ok(Request::Close),
+            _ => Err("\"cmd\" must be one of \"reset\", \"step\" or \"close\"".to_string())
+        }
+    }
+}
+
+fn error_json(msg: &str) -> String {
+    format!("{{\"error\": \"{}\"}}", msg.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Drives the environment through line-delimited JSON: one request per line on stdin, one response per line on stdout.
+/// Runs until stdin is closed or a `close` request is received
+pub fn serve() -> io::Result<()> {
+    let mut env = Environment::default();
+    let mut stdout = io::stdout().lock();
+
+    for line in io::stdin().lock().lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let response = match Request::parse(&line) {
+            Ok(Request::Reset { seed }) => {
+                let seed = seed.unwrap_or_else(rand::random);
+                format!("{{\"seed\": {}, \"observation\": {}}}", seed, env.reset(seed).to_json())
+            }
+            Ok(Request::Step { action, frames }) => {
+                let mut result: Option<StepResult> = None;
+                let mut reward = 0.0;
+
+                for _ in 0 .. frames {
+                    match env.step(action) {
+                        Some(step) => {
+                            reward += step.reward;
+                            result = Some(step);
+                        }
+                        None => break
+                    }
+
+                    if env.is_done() {
+                        break;
+                    }
+                }
+
+                match result {
+                    Some(result) => format!(
+                        "{{\"observation\": {}, \"reward\": {}, \"done\": {}, \"score\": {}, \"death_cause\": {}}}",
+                        result.observation.to_json(), reward, result.done, env.score(),
+                        env.death_cause().map_or("null".to_string(), |cause| format!("\"{}\"", cause.name()))
+                    ),
+                    None => error_json("no episode is running, send a reset first")
+                }
+            }
+            Ok(Request::Close) => break,
+            Err(e) => error_json(&e)
+        };
+
+        writeln!(stdout, "{}", response)?;
+        stdout.flush()?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn string(line: &str) -> Result<String, String> {
+        match parse_object(line)?.remove("s") {
+            Some(Value::String(s)) => Ok(s),
+            other => panic!("expected a string, got {:?}", other)
+        }
+    }
+
+    #[test]
+    fn seeds_above_f64_precision_are_exact() {
+        for seed in [(1 << 53) + 1, u64::MAX - 1, u64::MAX] {
+            let request = Request::parse(&format!("{{\"cmd\": \"reset\", \"seed\": {}}}", seed));
+            assert_eq!(request, Ok(Request::Reset { seed: Some(seed) }));
+        }
+
+        assert!(Request::parse("{\"cmd\": \"reset\", \"seed\": 18446744073709551616}").is_err());
+        assert_eq!(Request::parse("{\"cmd\": \"reset\", \"seed\": null}"), Ok(Request::Reset { seed: None }));
+    }
+
+    #[test]
+    fn negative_and_fractional_integers_are_rejected() {
+        for value in ["-1", "2.5", "4.0", "1e3", "-0"] {
+            assert!(Request::parse(&format!("{{\"cmd\": \"reset\", \"seed\": {}}}", value)).is_err(), "seed {}", value);
+            assert!(Request::parse(&format!("{{\"cmd\": \"step\", \"frames\": {}}}", value)).is_err(), "frames {}", value);
+        }
+
+        assert!(Request::parse("{\"cmd\": \"reset\", \"seed\": \"5\"}").is_err());
+    }
+
+    #[test]
+    fn step_fields() {
+        assert_eq!(
+            Request::parse("{\"cmd\": \"step\", \"jump\": true, \"frames\": 0}"),
+            Ok(Request::Step { action: EnvAction { jump: true, sprint: false }, frames: 1 })
+        );
+
+        assert_eq!(
+            Request::parse(" { \"sprint\" : true , \"cmd\" : \"step\" } "),
+            Ok(Request::Step { action: EnvAction { jump: false, sprint: true }, frames: 1 })
+        );
+
+        assert!(Request::parse("{\"cmd\": \"step\", \"jump\": 1}").is_err());
+        assert!(Request::parse("{\"cmd\": \"jump\"}").is_err());
+        assert_eq!(Request::parse("{\"cmd\": \"close\"}"), Ok(Request::Close));
+    }
+
+    #[test]
+    fn escapes_are_decoded() {
+        assert_eq!(string(r#"{"s": "a\"b\\c\/d"}"#), Ok("a\"b\\c/d".to_string()));
+        assert_eq!(string(r#"{"s": "\b\f\n\r\t"}"#), Ok("\u{8}\u{c}\n\r\t".to_string()));
+        assert_eq!(string(r#"{"s": "\u0041\u00e9\u20AC"}"#), Ok("A\u{e9}\u{20ac}".to_string()));
+        assert_eq!(string(r#"{"s": "\ud83d\ude00"}"#), Ok("\u{1f600}".to_string()));
+        assert_eq!(Request::parse(r#"{"cmd": "\u0063lose"}"#), Ok(Request::Close));
+    }
+
+    #[test]
+    fn invalid_escapes_are_rejected() {
+        for escape in [r"\x", r"\u12", r"\u12g4", r"\ud83d", r"\ud83dx", r"\ud83d\u0041", r"\ude00", "\\"] {
+            assert!(parse_object(&format!("{{\"s\": \"{}\"}}", escape)).is_err(), "{}", escape);
+        }
+    }
+
+    #[test]
+    fn trailing_characters_are_rejected() {
+        assert!(parse_object("{} ").is_ok());
+        assert!(parse_object("{}x").is_err());
+        assert!(parse_object("{\"cmd\": \"close\"}}").is_err());
+        assert!(parse_object("{\"cmd\": \"close\"} {}").is_err());
+        assert!(parse_object("{\"cmd\": \"close\",}").is_err());
+        assert!(parse_object("{\"cmd\": \"close\"").is_err());
+    }
+}
\ No newline at end of file
@@ -17,10 +17,10 @@ You should have received a copy of the GNU Affero General Public License
 along with !pong.  If not, see <http://www.gnu.org/licenses/>.
  */
 
-use rand::{rngs::ThreadRng, Rng};
+use rand::{rngs::StdRng, Rng};
 use raylib::{color::Color, math::Vector2, prelude::RaylibDraw};
 
-use crate::{FrameInfo, FG, INTERNAL_RESOLUTION, LIFESPAN_DECREASE, MAX_PARTICLE_QTY, MIN_PARTICLE_QTY, PARTICLE_SIZE, PARTICLE_VELOCITY_MULTIPLIER};
+use crate::{obstacle::gravity_well::{total_force, Field}, FrameInfo, FG, INTERNAL_RESOLUTION, LIFESPAN_DECREASE, MAX_PARTICLE_QTY, MIN_PARTICLE_QTY, PARTICLE_SIZE, PARTICLE_VELOCITY_MULTIPLIER};
 
 #[derive(Debug, Clone)]
 pub struct Particle {
@@ -34,7 +34,7 @@ pub struct Particle {
 }
 
 impl Particle {
-    pub fn new(pos: Vector2, max_init_velocity: f32, color: Color, rng: &mut ThreadRng) -> Self {
+    pub fn new(pos: Vector2, max_init_velocity: f32, color: Color, rng: &mut StdRng) -> Self {
         let mut velocity = Vector2 {
             x: rng.random_range(-1.0..1.0),
             y: rng.random_range(-1.0..1.0),
@@ -52,7 +52,6 @@ impl Particle {
         }
     }
 
-    #[allow(unused)]
     pub fn apply_force(&mut self, f: Vector2) {
         self.acceleration += f;
     }
@@ -112,7 +111,7 @@ impl Explosion {
         }
     }
 
-    pub fn explode(&mut self, max_velocity: f32, rainbow: bool, rng: &mut ThreadRng) {
+    pub fn explode(&mut self, max_velocity: f32, rainbow: bool, rng: &mut StdRng) {
         let amt = rng.random_range(MIN_PARTICLE_QTY..MAX_PARTICLE_QTY);
         for i in 0 .. amt {
             let color = {
@@ -127,11 +126,23 @@ impl Explosion {
         }
     }
 
-    pub fn explode_with_pos(&mut self, pos: Vector2, max_velocity: f32, rainbow: bool, rng: &mut ThreadRng) {
+    pub fn explode_with_pos(&mut self, pos: Vector2, max_velocity: f32, rainbow: bool, rng: &mut StdRng) {
         self.pos = pos;
         self.explode(max_velocity, rainbow, rng);
     }
 
+    /// Adds a single particle at `pos`, for trails
+    pub fn emit(&mut self, pos: Vector2, max_velocity: f32, color: Color, rng: &mut StdRng) {
+        self.particles.push(Particle::new(pos, max_velocity, color, rng));
+    }
+
+    /// Pulls the particles toward gravity wells, once per reference frame
+    pub fn pull(&mut self, fields: &[Field]) {
+        for particle in self.particles.iter_mut() {
+            particle.apply_force(total_force(fields, particle.pos));
+        }
+    }
+
     pub fn update(&mut self, frame_info: FrameInfo) {
         for particle in self.particles.iter_mut() {
             particle.update(frame_info);
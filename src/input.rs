@@ -0,0 +1,538 @@
+/*
+Copyright (C) 2025 Amari Calipso
+
+This file is part of !pong.
+
+!pong is free software: you can redistribute it and/or modify
+it under the terms of the GNU Affero General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+!pong is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Affero General Public License for more details.
+
+You should have received a copy of the GNU Affero General Public License
+along with !pong.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use raylib::{color::Color, ffi::{GamepadButton, Gesture, KeyboardKey, MouseButton}, math::Rectangle, prelude::RaylibDraw, RaylibHandle};
+
+use crate::{utils::point_in_rect, MAX_GAMEPADS, TOUCH_ZONE_MARGIN, TOUCH_ZONE_MIN_BAR, TOUCH_ZONE_PRESSED_ALPHA, TOUCH_ZONE_TEXT_HEIGHT};
+
+macro_rules! keys {
+    ($($name: ident),+ $(,)?) => {
+        &[$((KeyboardKey::$name, stringify!($name))),+]
+    };
+}
+
+/// Keys that can be bound to actions, along with the names used to save them
+const BINDABLE_KEYS: &[(KeyboardKey, &str)] = keys!(
+    KEY_A, KEY_B, KEY_C, KEY_D, KEY_E, KEY_F, KEY_G, KEY_H, KEY_I, KEY_J, KEY_K, KEY_L, KEY_M,
+    KEY_N, KEY_O, KEY_P, KEY_Q, KEY_R, KEY_S, KEY_T, KEY_U, KEY_V, KEY_W, KEY_X, KEY_Y, KEY_Z,
+    KEY_ZERO, KEY_ONE, KEY_TWO, KEY_THREE, KEY_FOUR, KEY_FIVE, KEY_SIX, KEY_SEVEN, KEY_EIGHT, KEY_NINE,
+    KEY_SPACE, KEY_ENTER, KEY_TAB, KEY_BACKSPACE, KEY_ESCAPE,
+    KEY_UP, KEY_DOWN, KEY_LEFT, KEY_RIGHT,
+    KEY_LEFT_SHIFT, KEY_RIGHT_SHIFT, KEY_LEFT_CONTROL, KEY_RIGHT_CONTROL, KEY_LEFT_ALT, KEY_RIGHT_ALT,
+    KEY_COMMA, KEY_PERIOD, KEY_SLASH, KEY_SEMICOLON, KEY_APOSTROPHE, KEY_MINUS, KEY_EQUAL,
+    KEY_LEFT_BRACKET, KEY_RIGHT_BRACKET, KEY_BACKSLASH, KEY_GRAVE,
+);
+
+const BINDABLE_MOUSE_BUTTONS: &[(MouseButton, &str)] = &[
+    (MouseButton::MOUSE_BUTTON_LEFT,   "MOUSE_LEFT"),
+    (MouseButton::MOUSE_BUTTON_RIGHT,  "MOUSE_RIGHT"),
+    (MouseButton::MOUSE_BUTTON_MIDDLE, "MOUSE_MIDDLE"),
+];
+
+const BINDABLE_GESTURES: &[(Gesture, &str)] = &[
+    (Gesture::GESTURE_TAP,         "TAP"),
+    (Gesture::GESTURE_DOUBLETAP,   "DOUBLETAP"),
+    (Gesture::GESTURE_SWIPE_LEFT,  "SWIPE_LEFT"),
+    (Gesture::GESTURE_SWIPE_RIGHT, "SWIPE_RIGHT"),
+    (Gesture::GESTURE_SWIPE_UP,    "SWIPE_UP"),
+    (Gesture::GESTURE_SWIPE_DOWN,  "SWIPE_DOWN"),
+];
+
+const BINDABLE_GAMEPAD_BUTTONS: &[(GamepadButton, &str)] = &[
+    (GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_DOWN,  "PAD_A"),
+    (GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_RIGHT, "PAD_B"),
+    (GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_LEFT,  "PAD_X"),
+    (GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_UP,    "PAD_Y"),
+    (GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_UP,     "PAD_UP"),
+    (GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_DOWN,   "PAD_DOWN"),
+    (GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_LEFT,   "PAD_LEFT"),
+    (GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_RIGHT,  "PAD_RIGHT"),
+    (GamepadButton::GAMEPAD_BUTTON_LEFT_TRIGGER_1,   "PAD_LB"),
+    (GamepadButton::GAMEPAD_BUTTON_RIGHT_TRIGGER_1,  "PAD_RB"),
+    // raylib reports analog triggers as buttons too, so they can be held like any other
+    (GamepadButton::GAMEPAD_BUTTON_LEFT_TRIGGER_2,   "PAD_LT"),
+    (GamepadButton::GAMEPAD_BUTTON_RIGHT_TRIGGER_2,  "PAD_RT"),
+    (GamepadButton::GAMEPAD_BUTTON_MIDDLE_LEFT,      "PAD_SELECT"),
+    (GamepadButton::GAMEPAD_BUTTON_MIDDLE_RIGHT,     "PAD_START"),
+    (GamepadButton::GAMEPAD_BUTTON_LEFT_THUMB,       "PAD_LS"),
+    (GamepadButton::GAMEPAD_BUTTON_RIGHT_THUMB,      "PAD_RS"),
+];
+
+fn available_gamepads(rl: &RaylibHandle) -> impl Iterator<Item = i32> + '_ {
+    (0 .. MAX_GAMEPADS).filter(|gamepad| rl.is_gamepad_available(*gamepad))
+}
+
+/// Whether `button` was pressed this frame on any connected gamepad
+pub fn gamepad_button_pressed(rl: &RaylibHandle, button: GamepadButton) -> bool {
+    available_gamepads(rl).any(|gamepad| rl.is_gamepad_button_pressed(gamepad, button))
+}
+
+/// Keeps track of connected gamepads, to notice when they're plugged in or out
+#[derive(Debug, Default)]
+pub struct Gamepads {
+    connected: [bool; MAX_GAMEPADS as usize]
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GamepadEvent {
+    Connected, Disconnected
+}
+
+impl Gamepads {
+    /// Returns what changed since the last call, if anything.
+    /// If more than one gamepad changed on the same frame, disconnections take priority
+    pub fn update(&mut self, rl: &RaylibHandle) -> Option<GamepadEvent> {
+        let mut event = None;
+
+        for gamepad in 0 .. MAX_GAMEPADS {
+            let available = rl.is_gamepad_available(gamepad);
+            let connected = &mut self.connected[gamepad as usize];
+
+            if available != *connected {
+                *connected = available;
+
+                if available {
+                    event = event.or(Some(GamepadEvent::Connected));
+                } else {
+                    event = Some(GamepadEvent::Disconnected);
+                }
+            }
+        }
+
+        event
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Jump, Sprint, Pause, Restart
+}
+
+impl Action {
+    pub const ALL: [Action; 4] = [Action::Jump, Action::Sprint, Action::Pause, Action::Restart];
+    /// Actions that drive the simulation. Programmatic players only control these,
+    /// pausing and restarting are always left to the devices
+    pub const GAMEPLAY: [Action; 2] = [Action::Jump, Action::Sprint];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Action::Jump => "JUMP",
+            Action::Sprint => "SPRINT",
+            Action::Pause => "PAUSE",
+            Action::Restart => "RESTART",
+        }
+    }
+
+    /// Name used to save the action's bindings
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::Jump => "jump",
+            Action::Sprint => "sprint",
+            Action::Pause => "pause",
+            Action::Restart => "restart",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ActionState {
+    /// The action started this frame
+    pub pressed: bool,
+    /// The action is being held
+    pub down: bool,
+    /// The action stopped this frame
+    pub released: bool
+}
+
+impl ActionState {
+    /// State of an action that was held on the previous frame if `was_down`, and is held now if `down`
+    pub fn transition(was_down: bool, down: bool) -> Self {
+        Self {
+            pressed: down && !was_down,
+            down,
+            released: !down && was_down
+        }
+    }
+
+    /// Packs the state into the lowest three bits, as stored in replays
+    pub fn to_bits(self) -> u8 {
+        self.pressed as u8 | (self.down as u8) << 1 | (self.released as u8) << 2
+    }
+
+    pub fn from_bits(bits: u8) -> Self {
+        Self {
+            pressed: bits & 1 != 0,
+            down: bits & 2 != 0,
+            released: bits & 4 != 0
+        }
+    }
+
+    fn merge(&mut self, other: ActionState) {
+        self.pressed |= other.pressed;
+        self.down |= other.down;
+        self.released |= other.released;
+    }
+}
+
+/// State of every action on a single frame
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Actions([ActionState; Action::ALL.len()]);
+
+impl Actions {
+    pub fn get(&self, action: Action) -> ActionState {
+        self.0[action as usize]
+    }
+
+    pub fn set(&mut self, action: Action, state: ActionState) {
+        self.0[action as usize] = state;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Binding {
+    Key(KeyboardKey),
+    Mouse(MouseButton),
+    /// Button on any of the connected gamepads
+    Gamepad(GamepadButton),
+    /// Gestures are instantaneous, so they count as both a press and a release, toggling hold-based actions
+    Gesture(Gesture)
+}
+
+impl Binding {
+    /// Name used to save the binding
+    pub fn name(self) -> &'static str {
+        match self {
+            Binding::Key(key) => BINDABLE_KEYS.iter().find(|(x, _)| *x == key).map(|(_, name)| *name),
+            Binding::Mouse(button) => BINDABLE_MOUSE_BUTTONS.iter().find(|(x, _)| *x == button).map(|(_, name)| *name),
+            Binding::Gamepad(button) => BINDABLE_GAMEPAD_BUTTONS.iter().find(|(x, _)| *x == button).map(|(_, name)| *name),
+            Binding::Gesture(gesture) => BINDABLE_GESTURES.iter().find(|(x, _)| *x == gesture).map(|(_, name)| *name),
+        }.unwrap_or("UNKNOWN")
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        BINDABLE_KEYS.iter().find(|(_, x)| x.eq_ignore_ascii_case(name)).map(|(key, _)| Binding::Key(*key))
+            .or_else(|| BINDABLE_MOUSE_BUTTONS.iter().find(|(_, x)| x.eq_ignore_ascii_case(name)).map(|(button, _)| Binding::Mouse(*button)))
+            .or_else(|| BINDABLE_GAMEPAD_BUTTONS.iter().find(|(_, x)| x.eq_ignore_ascii_case(name)).map(|(button, _)| Binding::Gamepad(*button)))
+            .or_else(|| BINDABLE_GESTURES.iter().find(|(_, x)| x.eq_ignore_ascii_case(name)).map(|(gesture, _)| Binding::Gesture(*gesture)))
+    }
+
+    /// Short name to show in menus
+    pub fn label(self) -> String {
+        match self {
+            Binding::Key(_) => self.name().trim_start_matches("KEY_").replace('_', " "),
+            Binding::Mouse(MouseButton::MOUSE_BUTTON_LEFT) => String::from("LMB"),
+            Binding::Mouse(MouseButton::MOUSE_BUTTON_RIGHT) => String::from("RMB"),
+            Binding::Mouse(MouseButton::MOUSE_BUTTON_MIDDLE) => String::from("MMB"),
+            _ => self.name().replace('_', " "),
+        }
+    }
+
+    fn state(self, rl: &RaylibHandle) -> ActionState {
+        match self {
+            Binding::Key(key) => ActionState {
+                pressed: rl.is_key_pressed(key),
+                down: rl.is_key_down(key),
+                released: rl.is_key_released(key)
+            },
+            Binding::Mouse(button) => ActionState {
+                pressed: rl.is_mouse_button_pressed(button),
+                down: rl.is_mouse_button_down(button),
+                released: rl.is_mouse_button_released(button)
+            },
+            Binding::Gamepad(button) => {
+                let mut state = ActionState::default();
+
+                for gamepad in available_gamepads(rl) {
+                    state.merge(ActionState {
+                        pressed: rl.is_gamepad_button_pressed(gamepad, button),
+                        down: rl.is_gamepad_button_down(gamepad, button),
+                        released: rl.is_gamepad_button_released(gamepad, button)
+                    });
+                }
+
+                state
+            }
+            Binding::Gesture(gesture) => {
+                let detected = rl.is_gesture_detected(gesture);
+                ActionState {
+                    pressed: detected,
+                    down: false,
+                    released: detected
+                }
+            }
+        }
+    }
+
+    /// Returns the first bindable input that was pressed this frame, if any
+    pub fn pressed(rl: &RaylibHandle) -> Option<Self> {
+        BINDABLE_KEYS.iter().find(|(key, _)| rl.is_key_pressed(*key)).map(|(key, _)| Binding::Key(*key))
+            .or_else(|| BINDABLE_MOUSE_BUTTONS.iter().find(|(button, _)| rl.is_mouse_button_pressed(*button)).map(|(button, _)| Binding::Mouse(*button)))
+            .or_else(|| BINDABLE_GAMEPAD_BUTTONS.iter().find(|(button, _)| gamepad_button_pressed(rl, *button)).map(|(button, _)| Binding::Gamepad(*button)))
+            .or_else(|| BINDABLE_GESTURES.iter().find(|(gesture, _)| rl.is_gesture_detected(*gesture)).map(|(gesture, _)| Binding::Gesture(*gesture)))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Bindings([Vec<Binding>; Action::ALL.len()]);
+
+impl Default for Bindings {
+    fn default() -> Self {
+        Self([
+            vec![
+                Binding::Key(KeyboardKey::KEY_UP),
+                Binding::Key(KeyboardKey::KEY_SPACE),
+                Binding::Mouse(MouseButton::MOUSE_BUTTON_LEFT),
+                Binding::Gamepad(GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_DOWN),
+                Binding::Gamepad(GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_UP),
+                Binding::Gesture(Gesture::GESTURE_TAP)
+            ],
+            vec![
+                Binding::Key(KeyboardKey::KEY_RIGHT),
+                Binding::Key(KeyboardKey::KEY_LEFT),
+                Binding::Key(KeyboardKey::KEY_Z),
+                Binding::Mouse(MouseButton::MOUSE_BUTTON_RIGHT),
+                Binding::Gamepad(GamepadButton::GAMEPAD_BUTTON_RIGHT_TRIGGER_2),
+                Binding::Gamepad(GamepadButton::GAMEPAD_BUTTON_LEFT_TRIGGER_2),
+                Binding::Gamepad(GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_LEFT),
+                Binding::Gesture(Gesture::GESTURE_SWIPE_LEFT),
+                Binding::Gesture(Gesture::GESTURE_SWIPE_RIGHT)
+            ],
+            vec![
+                Binding::Key(KeyboardKey::KEY_ESCAPE),
+                Binding::Key(KeyboardKey::KEY_P),
+                Binding::Gamepad(GamepadButton::GAMEPAD_BUTTON_MIDDLE_RIGHT),
+                Binding::Gesture(Gesture::GESTURE_SWIPE_DOWN)
+            ],
+            vec![
+                Binding::Key(KeyboardKey::KEY_R),
+                Binding::Gamepad(GamepadButton::GAMEPAD_BUTTON_MIDDLE_LEFT)
+            ]
+        ])
+    }
+}
+
+impl Bindings {
+    pub fn get(&self, action: Action) -> &[Binding] {
+        &self.0[action as usize]
+    }
+
+    /// Replaces the bindings of `action`. Bindings already used by other actions are skipped
+    pub fn set(&mut self, action: Action, bindings: Vec<Binding>) {
+        self.0[action as usize].clear();
+
+        for binding in bindings {
+            let _ = self.bind(action, binding);
+        }
+    }
+
+    /// Removes every binding of every action
+    pub fn clear(&mut self) {
+        for bindings in &mut self.0 {
+            bindings.clear();
+        }
+    }
+
+    /// Gives the default bindings to actions that have none. Defaults already used by other actions are skipped
+    pub fn fill_defaults(&mut self) {
+        let defaults = Self::default();
+
+        for action in Action::ALL {
+            if self.get(action).is_empty() {
+                self.set(action, defaults.get(action).to_vec());
+            }
+        }
+    }
+
+    /// Returns the action `binding` is assigned to, if any
+    pub fn find(&self, binding: Binding) -> Option<Action> {
+        Action::ALL.into_iter().find(|action| self.get(*action).contains(&binding))
+    }
+
+    /// Adds `binding` to `action`. If it's already used by another action, nothing is changed and that action is returned
+    pub fn bind(&mut self, action: Action, binding: Binding) -> Result<(), Action> {
+        match self.find(binding) {
+            Some(other) if other != action => Err(other),
+            Some(_) => Ok(()),
+            None => {
+                self.0[action as usize].push(binding);
+                Ok(())
+            }
+        }
+    }
+
+    /// Removes the most recently added binding of `action`. The last one is never removed,
+    /// so actions can't become unreachable. Returns whether a binding was removed
+    pub fn unbind_last(&mut self, action: Action) -> bool {
+        let bindings = &mut self.0[action as usize];
+
+        if bindings.len() > 1 {
+            bindings.pop();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Menu label for `action`, listing its first bindings
+    pub fn label(&self, action: Action) -> String {
+        const SHOWN: usize = 2;
+
+        let bindings = self.get(action);
+        let mut label = format!("{}: ", action.label());
+        label += &bindings.iter().take(SHOWN).map(|x| x.label()).collect::<Vec<_>>().join(", ");
+
+        if bindings.len() > SHOWN {
+            label += &format!(" +{}", bindings.len() - SHOWN);
+        }
+
+        label
+    }
+
+    /// Reads the state of every action. When `touch_zones` is set, the on-screen zones replace
+    /// mouse and gesture bindings of the actions they handle, since touches also emulate those
+    pub fn poll(&self, rl: &RaylibHandle, touch_zones: bool) -> Actions {
+        let mut actions = Actions::default();
+
+        for action in Action::ALL {
+            for binding in self.get(action) {
+                if touch_zones && TouchZones::handles(action) && matches!(binding, Binding::Mouse(_) | Binding::Gesture(_)) {
+                    continue;
+                }
+
+                actions.0[action as usize].merge(binding.state(rl));
+            }
+        }
+
+        actions
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TouchLayout {
+    Off, JumpLeft, JumpRight
+}
+
+impl TouchLayout {
+    pub const ALL: [TouchLayout; 3] = [TouchLayout::Off, TouchLayout::JumpLeft, TouchLayout::JumpRight];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            TouchLayout::Off => "OFF",
+            TouchLayout::JumpLeft => "JUMP LEFT",
+            TouchLayout::JumpRight => "JUMP RIGHT",
+        }
+    }
+}
+
+/// On-screen touch controls: one half of the window jumps, the other one sprints while held
+#[derive(Debug, Default)]
+pub struct TouchZones {
+    jump_down: bool,
+    sprint_down: bool
+}
+
+impl TouchZones {
+    fn handles(action: Action) -> bool {
+        matches!(action, Action::Jump | Action::Sprint)
+    }
+
+    /// Jump and sprint zones, in window coordinates
+    fn rects(layout: TouchLayout, width: f32, height: f32) -> Option<(Rectangle, Rectangle)> {
+        let left = Rectangle { x: 0.0, y: 0.0, width: width / 2.0, height };
+        let right = Rectangle { x: width / 2.0, y: 0.0, width: width / 2.0, height };
+
+        match layout {
+            TouchLayout::Off => None,
+            TouchLayout::JumpLeft => Some((left, right)),
+            TouchLayout::JumpRight => Some((right, left)),
+        }
+    }
+
+    /// Updates the zones from the current touch points, adding their state to `actions`
+    pub fn poll(&mut self, rl: &RaylibHandle, layout: TouchLayout, actions: &mut Actions) {
+        let Some((jump, sprint)) = Self::rects(layout, rl.get_screen_width() as f32, rl.get_screen_height() as f32) else {
+            self.jump_down = false;
+            self.sprint_down = false;
+            return;
+        };
+
+        let mut jump_down = false;
+        let mut sprint_down = false;
+
+        // every touch point counts, so sprint can be held while jumping with another finger
+        for i in 0 .. rl.get_touch_point_count() {
+            let pos = rl.get_touch_position(i);
+            jump_down |= point_in_rect(pos, jump);
+            sprint_down |= point_in_rect(pos, sprint);
+        }
+
+        actions.0[Action::Jump as usize].merge(ActionState::transition(self.jump_down, jump_down));
+        actions.0[Action::Sprint as usize].merge(ActionState::transition(self.sprint_down, sprint_down));
+
+        self.jump_down = jump_down;
+        self.sprint_down = sprint_down;
+    }
+
+    /// Draws the zones' labels in the letterbox bars around `game_box`, if there's room for them
+    pub fn draw(&self, draw: &mut (impl RaylibDraw + std::ops::Deref<Target = RaylibHandle>), layout: TouchLayout, game_box: Rectangle, width: f32, height: f32, color: Color) {
+        let Some((jump, sprint)) = Self::rects(layout, width, height) else {
+            return;
+        };
+
+        for (zone, label, down) in [(jump, "JUMP", self.jump_down), (sprint, "SPRINT", self.sprint_down)] {
+            // only the part of the zone that falls outside the game box
+            let bar = {
+                if game_box.x >= TOUCH_ZONE_MIN_BAR {
+                    if zone.x < game_box.x {
+                        Rectangle { x: 0.0, y: 0.0, width: game_box.x, height }
+                    } else {
+                        Rectangle { x: game_box.x + game_box.width, y: 0.0, width: width - game_box.x - game_box.width, height }
+                    }
+                } else if height - game_box.y - game_box.height >= TOUCH_ZONE_MIN_BAR {
+                    let y = game_box.y + game_box.height;
+                    Rectangle { x: zone.x, y, width: zone.width, height: height - y }
+                } else {
+                    continue;
+                }
+            };
+
+            let bar = Rectangle {
+                x: bar.x + TOUCH_ZONE_MARGIN,
+                y: bar.y + TOUCH_ZONE_MARGIN,
+                width: bar.width - TOUCH_ZONE_MARGIN * 2.0,
+                height: bar.height - TOUCH_ZONE_MARGIN * 2.0
+            };
+
+            if down {
+                draw.draw_rectangle_rec(bar, Color { a: TOUCH_ZONE_PRESSED_ALPHA, ..color });
+            }
+
+            draw.draw_rectangle_lines(bar.x as i32, bar.y as i32, bar.width as i32, bar.height as i32, color);
+
+            let size = draw.measure_text(label, TOUCH_ZONE_TEXT_HEIGHT);
+            draw.draw_text(
+                label,
+                (bar.x + bar.width / 2.0) as i32 - size / 2,
+                (bar.y + bar.height / 2.0) as i32 - TOUCH_ZONE_TEXT_HEIGHT / 2,
+                TOUCH_ZONE_TEXT_HEIGHT,
+                color
+            );
+        }
+    }
+}
\ No newline at end of file
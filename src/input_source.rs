@@ -0,0 +1,292 @@
+/*
+Copyright (C) 2025 Amari Calipso
+
+This file is part of !pong.
+
+!pong is free software: you can redistribute it and/or modify
+it under the terms of the GNU Affero General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+!pong is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Affero General Public License for more details.
+
+You should have received a copy of the GNU Affero General Public License
+along with !pong.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use std::{fs, io::{Error, ErrorKind}, path::Path};
+
+use enum_dispatch::enum_dispatch;
+use raylib::RaylibHandle;
+
+use crate::{bot::Bot, input::{Action, ActionState, Actions, TouchLayout, TouchZones}, obstacle::AnyObstacle, pad::Pad, player::Player, settings::Settings};
+
+/// The parts of the game input sources can react to
+pub struct GameView<'a> {
+    pub player: &'a Player,
+    pub left_pad: &'a Pad,
+    pub right_pad: &'a Pad,
+    pub obstacles: &'a [AnyObstacle],
+    pub sprint_amount: f32,
+    pub sprint_cooldown: f32,
+    /// How fast obstacles move compared to the player, as slowed down by pickups
+    pub obstacle_time_scale: f32
+}
+
+/// Everything an input source can look at to decide the actions of a frame
+pub struct InputFrame<'a> {
+    /// `None` when running headless, with no window or devices
+    pub rl: Option<&'a RaylibHandle>,
+    pub settings: &'a Settings,
+    /// Index of the simulation frame being polled, counting from the start of the session
+    pub frame: u64,
+    pub game: GameView<'a>
+}
+
+/// Timing of a simulation frame, stored in replays so they advance exactly like the recorded session
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrameTiming {
+    pub delta_time: f32,
+    pub in_reference_frame: bool
+}
+
+#[enum_dispatch(AnyInputSource)]
+pub trait InputSource {
+    /// Produces the state of every action for the given frame. Called once per simulation frame
+    fn poll(&mut self, frame: &InputFrame) -> Actions;
+
+    /// Timing the last polled frame should run with, if the source dictates it
+    fn timing(&self) -> Option<FrameTiming> {
+        None
+    }
+}
+
+/// Programmatic player, driving the gameplay actions in place of the devices
+#[enum_dispatch]
+#[derive(Debug, Clone)]
+pub enum AnyInputSource {
+    ReplayInput,
+    ScriptedInput,
+    Bot
+}
+
+/// Keyboard, mouse, gamepads and touch zones, as configured in the settings
+#[derive(Debug, Default)]
+pub struct LiveInput {
+    pub touch_zones: TouchZones
+}
+
+impl InputSource for LiveInput {
+    fn poll(&mut self, frame: &InputFrame) -> Actions {
+        let Some(rl) = frame.rl else {
+            return Actions::default();
+        };
+
+        let touch = frame.settings.touch != TouchLayout::Off;
+        let mut actions = frame.settings.bindings.poll(rl, touch);
+        self.touch_zones.poll(rl, frame.settings.touch, &mut actions);
+        actions
+    }
+}
+
+fn invalid_data(line: usize, msg: &str) -> Error {
+    Error::new(ErrorKind::InvalidData, format!("line {}: {}", line + 1, msg))
+}
+
+/// Recorded session: the seed of the random number generator and the lives per game, 
+/// followed by the timing and actions of every simulation frame
+#[derive(Debug, Clone, Default)]
+pub struct Replay {
+    pub seed: u64,
+    pub lives: u32,
+    frames: Vec<(FrameTiming, Actions)>
+}
+
+impl Replay {
+    pub fn new(seed: u64, lives: u32) -> Self {
+        Self {
+            seed,
+            lives,
+            frames: Vec::new()
+        }
+    }
+
+    pub fn push(&mut self, timing: FrameTiming, actions: Actions) {
+        self.frames.push((timing, actions));
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let contents = fs::read_to_string(path)?;
+        let mut lines = contents.lines().enumerate().peekable();
+
+        let seed = lines.next()
+            .and_then(|(_, line)| line.strip_prefix("seed="))
+            .and_then(|seed| seed.trim().parse().ok())
+            .ok_or_else(|| invalid_data(0, "expected seed"))?;
+
+        // missing from replays recorded before lives were added
+        let lives = lines.next_if(|(_, line)| line.starts_with("lives="))
+            .map(|(i, line)| line["lives=".len() ..].trim().parse().map_err(|_| invalid_data(i, "invalid lives")))
+            .transpose()?
+            .unwrap_or(1);
+
+        let mut replay = Self::new(seed, lives);
+
+        for (i, line) in lines {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let mut parts = line.split_whitespace();
+
+            let delta_time = parts.next().and_then(|x| x.parse().ok())
+                .ok_or_else(|| invalid_data(i, "invalid delta time"))?;
+
+            let in_reference_frame = match parts.next() {
+                Some("1") => true,
+                Some("0") => false,
+                _ => return Err(invalid_data(i, "invalid reference frame flag"))
+            };
+
+            let states = parts.next()
+                .filter(|x| x.len() == Action::ALL.len())
+                .ok_or_else(|| invalid_data(i, "invalid actions"))?;
+
+            let mut actions = Actions::default();
+            for (action, digit) in Action::ALL.into_iter().zip(states.chars()) {
+                let bits = digit.to_digit(8).ok_or_else(|| invalid_data(i, "invalid actions"))?;
+                actions.set(action, ActionState::from_bits(bits as u8));
+            }
+
+            replay.push(FrameTiming { delta_time, in_reference_frame }, actions);
+        }
+
+        Ok(replay)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        let mut contents = format!("seed={}\nlives={}\n", self.seed, self.lives);
+
+        for (timing, actions) in &self.frames {
+            let states: String = Action::ALL.into_iter()
+                .map(|action| char::from(b'0' + actions.get(action).to_bits()))
+                .collect();
+
+            contents += &format!("{} {} {}\n", timing.delta_time, timing.in_reference_frame as u8, states);
+        }
+
+        fs::write(path, contents)
+    }
+}
+
+/// Plays back a recorded session. Once it runs out of frames, no action is performed
+#[derive(Debug, Clone)]
+pub struct ReplayInput {
+    replay: Replay,
+    next: usize
+}
+
+impl ReplayInput {
+    pub fn new(replay: Replay) -> Self {
+        Self {
+            replay,
+            next: 0
+        }
+    }
+}
+
+impl InputSource for ReplayInput {
+    fn poll(&mut self, _frame: &InputFrame) -> Actions {
+        let actions = self.replay.frames.get(self.next)
+            .map(|(_, actions)| *actions)
+            .unwrap_or_default();
+
+        self.next += 1;
+        actions
+    }
+
+    fn timing(&self) -> Option<FrameTiming> {
+        self.replay.frames.get(self.next.checked_sub(1)?).map(|(timing, _)| *timing)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ScriptStep {
+    pub frame: u64,
+    pub action: Action,
+    /// Whether the action starts or stops being held
+    pub down: bool
+}
+
+/// Holds and releases actions on predetermined frames
+#[derive(Debug, Clone)]
+pub struct ScriptedInput {
+    steps: Vec<ScriptStep>,
+    next: usize,
+    held: [bool; Action::ALL.len()]
+}
+
+impl ScriptedInput {
+    pub fn new(mut steps: Vec<ScriptStep>) -> Self {
+        steps.sort_by_key(|step| step.frame);
+
+        Self {
+            steps,
+            next: 0,
+            held: [false; Action::ALL.len()]
+        }
+    }
+
+    /// Loads a script made of `<frame> <action> <down|up>` lines. Empty lines and lines starting with `#` are ignored
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let contents = fs::read_to_string(path)?;
+        let mut steps = Vec::new();
+
+        for (i, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut parts = line.split_whitespace();
+
+            let frame = parts.next().and_then(|x| x.parse().ok())
+                .ok_or_else(|| invalid_data(i, "invalid frame"))?;
+
+            let action = parts.next()
+                .and_then(|name| Action::ALL.into_iter().find(|action| action.name().eq_ignore_ascii_case(name)))
+                .ok_or_else(|| invalid_data(i, "invalid action"))?;
+
+            let down = match parts.next() {
+                Some("down") => true,
+                Some("up") => false,
+                _ => return Err(invalid_data(i, "expected \"down\" or \"up\""))
+            };
+
+            steps.push(ScriptStep { frame, action, down });
+        }
+
+        Ok(Self::new(steps))
+    }
+}
+
+impl InputSource for ScriptedInput {
+    fn poll(&mut self, frame: &InputFrame) -> Actions {
+        let was_held = self.held;
+
+        while let Some(step) = self.steps.get(self.next).filter(|step| step.frame <= frame.frame) {
+            self.held[step.action as usize] = step.down;
+            self.next += 1;
+        }
+
+        let mut actions = Actions::default();
+        for action in Action::ALL {
+            actions.set(action, ActionState::transition(was_held[action as usize], self.held[action as usize]));
+        }
+
+        actions
+    }
+}
\ No newline at end of file
@@ -17,14 +17,12 @@ You should have received a copy of the GNU Affero General Public License
 along with !pong.  If not, see <http://www.gnu.org/licenses/>.
  */
 
-use std::{cell::OnceCell, collections::HashMap, time::Instant};
+use std::{cell::OnceCell, collections::HashMap, env, fs, mem, ops::Range, path::PathBuf, process, time::Instant};
 
-use pad::Pad;
-use player::Player;
-use rand::{rngs::ThreadRng, seq::IteratorRandom, Rng};
-use raylib::{audio::{RaylibAudio, Sound, SoundAlias}, color::Color, ffi::{Gesture, KeyboardKey, MouseButton, TraceLogLevel}, math::{Rectangle, Vector2}, prelude::{RaylibDraw, RaylibTextureModeExt}, texture::{Image, RenderTexture2D}, window::{get_current_monitor, get_monitor_refresh_rate}, RaylibHandle};
+use rand::Rng;
+use raylib::{audio::{RaylibAudio, SoundAlias}, color::Color, ffi::{KeyboardKey, TraceLogLevel}, math::{Rectangle, Vector2}, prelude::{RaylibDraw, RaylibTextureModeExt}, texture::{Image, RenderTexture2D}, RaylibHandle};
 
-use crate::{bomb::Bomb, explosion::Explosion, obstacle::{explosion::ObstacleExplosion, rock::Rock, rocket::{self, Rocket}, AnyObstacle, Obstacle}, obstacle_grid::ObstacleGrid, utils::vec2};
+use crate::{input::{Action, Actions, Binding, Bindings, GamepadEvent, Gamepads}, input_source::{AnyInputSource, FrameTiming, InputFrame, InputSource, LiveInput, Replay, ReplayInput, ScriptedInput}, bot::Bot, menu::{Menu, MenuEvent}, settings::{Settings, SettingsEntry}, obstacle::AnyObstacle, world::{World, WorldEvent}, simulation::{OutputFormat, Summary}};
 
 const INTERNAL_RESOLUTION: Vector2 = Vector2 { x: 320.0, y: 180.0 };
 
@@ -34,14 +32,24 @@ const BOMB_TEST: bool = false; // default: false
 const ROCKETS_TEST: bool = false; // default: false
 const NOCLIP: bool = ROCKETS_TEST || COLLISION_TEST || false; // default: false
 const ROCKETS: bool = true; // default: true
-const SHOW_FPS: bool = false; // default: false
-const UNLIMITED_FPS: bool = false; // default: false
-const MAX_FPS: Option<u32> = None; // default: None
 
 const INTRO_TEXT: &str = "JUMP TO START";
 const INTRO_TEXT_HEIGHT: i32 = 10;
 const INTRO_TEXT_Y_OFFSET: i32 = 25;
+const ATTRACT_TEXT: &str = "DEMO";
 const SCORE_TEXT_HEIGHT: i32 = 10;
+const PAUSE_TEXT: &str = "PAUSED";
+const SETTINGS_TEXT: &str = "SETTINGS";
+const CONTROLS_TEXT: &str = "CONTROLS";
+const GAMEPAD_CONNECTED_TEXT: &str = "GAMEPAD CONNECTED";
+const GAMEPAD_DISCONNECTED_TEXT: &str = "GAMEPAD DISCONNECTED";
+const TOAST_TEXT_HEIGHT: i32 = 10;
+const TOAST_Y_OFFSET: i32 = 15;
+const MENU_TITLE_HEIGHT: i32 = 10;
+const MENU_TEXT_HEIGHT: i32 = 10;
+const TOUCH_ZONE_TEXT_HEIGHT: i32 = 20;
+const SETTINGS_FOLDER: &str = "not-pong";
+const SETTINGS_FILENAME: &str = "settings.cfg";
 
 const HIT_SOUND:    &[u8] = include_bytes!("../resources/hit.wav");
 const DEATH_SOUND:  &[u8] = include_bytes!("../resources/death.wav");
@@ -64,6 +72,8 @@ const SPRINT_LINE_POS: Vector2 = Vector2 { x: 10.0, y: 10.0 };
 const LIGHTNING_LINE_RPOS: Vector2 = Vector2 { x: 5.0, y: 6.5 };
 const NO_OBSTACLES_CENTER_ZONE: Vector2 = Vector2 { x: 50.0, y: 50.0 };
 const LIGHTNING_POS: Vector2 = Vector2 { x: SPRINT_LINE_POS.x + SPRINT_LINE_MAX_LENGTH + LIGHTNING_LINE_RPOS.x, y: LIGHTNING_LINE_RPOS.y };
+/// Top right corner of the lives counter, from the top right corner of the screen
+const LIVES_RPOS: Vector2 = Vector2 { x: 10.0, y: 10.0 };
 
 const PLAYER_SIZE: f32 = 5.0;
 const PAD_WALL_DISTANCE: f32 = 10.0;
@@ -85,6 +95,64 @@ const PLAYER_COUNT_OBST: u64 = if START_OBSTACLES_EARLY { 0 } else { 10 };
 const MOD_INCREMENT_DIFF: u64 = 5;
 const MIN_ROCKET_SPEED: f32 = 1.75;
 const MAX_ROCKET_SPEED: f32 = 3.5;
+const MISSILE_SIZE: f32 = 4.0;
+const MISSILE_SPEED: f32 = 1.1;
+const MISSILE_TURN_RATE: f32 = 0.025;
+const MISSILE_FUEL: f32 = 300.0;
+const MISSILE_SMOKE_VELOCITY: f32 = 0.3;
+const MISSILE_SMOKE_COLOR: Color = Color { r: 140, g: 140, b: 140, a: 255 };
+const MISSILE_PROBABILITY: u32 = 4;
+const MISSILE_SAFE_DISTANCE: f32 = 100.0;
+const MISSILE_TRICK_POINTS: u64 = 3;
+const PLAYER_COUNT_MISSILE: u64 = 50;
+const LASER_WIDTH: f32 = 3.0;
+const LASER_WARNING_TIME: f32 = 60.0;
+const LASER_FIRE_TIME: f32 = 30.0;
+const LASER_FLICKER_TIME: f32 = 4.0;
+const LASER_PROBABILITY: u32 = 4;
+const LASER_SAFE_DISTANCE: f32 = 30.0;
+const PLAYER_COUNT_LASER: u64 = 70;
+const GRAVITY_WELL_CORE: f32 = 4.0;
+const GRAVITY_WELL_RADIUS: f32 = 70.0;
+const GRAVITY_WELL_STRENGTH: f32 = 0.08;
+const GRAVITY_WELL_ROCK_PULL: f32 = 0.3;
+const GRAVITY_WELL_MIN_SPEED: f32 = 0.5;
+const GRAVITY_WELL_SPEED_RECOVERY: f32 = 0.02;
+const GRAVITY_WELL_LIFE: f32 = 480.0;
+const GRAVITY_WELL_COLLAPSE_TIME: f32 = 60.0;
+const GRAVITY_WELL_RING_SPEED: f32 = 0.4;
+const GRAVITY_WELL_PROBABILITY: u32 = 6;
+const GRAVITY_WELL_SAFE_DISTANCE: f32 = 80.0;
+const PLAYER_COUNT_GRAVITY_WELL: u64 = 90;
+const MINE_RADIUS: f32 = 4.0;
+const MINE_LIFE: f32 = 900.0;
+const MINE_ARM_TIME: f32 = 90.0;
+const MINE_SENSE_RADIUS: f32 = 70.0;
+const MINE_TRIGGER_RADIUS: f32 = 20.0;
+const MINE_FUSE_TIME: f32 = 30.0;
+const MINE_BLAST_RADIUS: f32 = 30.0;
+const MINE_BLAST_VELOCITY: f32 = 2.5;
+const MINE_SLOW_PULSE: f32 = 0.015;
+const MINE_FAST_PULSE: f32 = 0.15;
+const MINE_PROBABILITY: u32 = 5;
+const PLAYER_COUNT_MINE: u64 = 60;
+const ASTEROID_SIZE: f32 = 12.0;
+const ASTEROID_MIN_SPEED: f32 = 0.1;
+const ASTEROID_MAX_SPEED: f32 = 0.3;
+const ASTEROID_SPLIT_RATIO: f32 = 0.6;
+const ASTEROID_MIN_FRAGMENTS: u32 = 2;
+const ASTEROID_MAX_FRAGMENTS: u32 = 3;
+const ASTEROID_SPLIT_SPEED: f32 = 0.5;
+const ASTEROID_SPLIT_GRACE: f32 = 30.0;
+const ASTEROID_PROBABILITY: u32 = 5;
+const PLAYER_COUNT_ASTEROID: u64 = 40;
+const BARRIER_LENGTH: f32 = 20.0;
+const BARRIER_WIDTH: f32 = 3.0;
+const BARRIER_SPEED: f32 = 0.4;
+const BARRIER_PAUSE: f32 = 45.0;
+const BARRIER_LIFE: f32 = 900.0;
+const BARRIER_PROBABILITY: u32 = 5;
+const PLAYER_COUNT_BARRIER: u64 = 50;
 const START_DIFFICULTY: u16 = if START_OBSTACLES_EARLY { 1 } else { 0 };
 const BOMB_SIZE: f32 = 6.0;
 const BOMB_LIFE: f32 = 150.0;
@@ -111,6 +179,14 @@ const PARTICLE_VELOCITY_MULTIPLIER: f32 = 0.972;
 const OBSTACLE_GRID_DIV_X: f32 = 12.0;
 const OBSTACLE_GRID_DIV_Y: f32 = 6.0;
 const OBSTACLE_POS_VARIANCE: f32 = 6.0;
+const SPAWN_PREDICTION_FRAMES: u32 = REFERENCE_FRAMERATE as u32;
+const SPAWN_SAFE_RADIUS: f32 = 8.0;
+const ROCKET_SAFE_DISTANCE: f32 = 60.0;
+const ROCKET_LANE_MARGIN: f32 = 4.0;
+const ROCKET_LAUNCH_ATTEMPTS: u32 = 8;
+const PICKUP_MIN_PLAYER_DISTANCE: f32 = 40.0;
+const PICKUP_OBSTACLE_MARGIN: f32 = 6.0;
+const PICKUP_REACH_DISTANCE: f32 = 20.0;
 const ROCKET_SHAKE: f32 = 1.0;
 const OBSTACLE_COLLISION_MAX_VELOCITY: f32 = 0.8;
 const OBSTACLE_PROBABILITY: u16 = if COLLISION_TEST { 1 } else { 1000 };
@@ -118,7 +194,73 @@ const BOMB_ANGLE_INCREMENT: f32 = 0.05;
 const BOMB_PROBABILITY: u16 = if BOMB_TEST { 0 } else { 2500 };
 const BOMB_MIN_DESTROYED_OBSTACLES: usize = 1;
 const BOMB_MAX_DESTROYED_OBSTACLES: usize = 4;
+const SHIELD_SIZE: f32 = 7.0;
+const SHIELD_LIFE: f32 = 300.0;
+const SHIELD_PROBABILITY: u16 = 3000;
+const PLAYER_COUNT_SHIELD: u64 = 30;
+const SHIELD_OUTLINE_DISTANCE: f32 = 2.0;
+const SHIELD_BREAK_PITCH: f32 = 1.6;
+const SLOW_MOTION_SIZE: f32 = 7.0;
+const SLOW_MOTION_LIFE: f32 = 300.0;
+const SLOW_MOTION_PROBABILITY: u16 = 3000;
+const PLAYER_COUNT_SLOW_MOTION: u64 = 40;
+const SLOW_MOTION_DURATION: f32 = 240.0;
+const SLOW_MOTION_SCALE: f32 = 0.4;
+const SLOW_MOTION_PITCH: f32 = 0.6;
+const SLOW_MOTION_TINT: Color = Color { r: 170, g: 170, b: 170, a: 255 };
+const RAINBOW_SIZE: f32 = 6.0;
+const RAINBOW_LIFE: f32 = 240.0;
+const RAINBOW_PROBABILITY: u16 = 6000;
+const PLAYER_COUNT_RAINBOW: u64 = 60;
+const RAINBOW_DURATION: f32 = 360.0;
+const RAINBOW_FLASH_TIME: f32 = 90.0;
+const RAINBOW_SMASH_POINTS: u64 = 1;
+const WIDE_PAD_SIZE: f32 = 7.0;
+const WIDE_PAD_LIFE: f32 = 300.0;
+const WIDE_PAD_PROBABILITY: u16 = 3000;
+const PLAYER_COUNT_WIDE_PAD: u64 = 15;
+const WIDE_PAD_HEIGHT: f32 = 40.0;
+const WIDE_PAD_HITS: u32 = 6;
+const PAD_RESIZE_VELOCITY: f32 = 0.5;
+const MAGNET_SIZE: f32 = 7.0;
+const MAGNET_LIFE: f32 = 300.0;
+const MAGNET_PROBABILITY: u16 = 3000;
+const PLAYER_COUNT_MAGNET: u64 = 25;
+const MAGNET_DURATION: f32 = 600.0;
+const MAGNET_RADIUS: f32 = 60.0;
+const HOMING_STIFFNESS: f32 = 0.0025;
+const HOMING_DAMPING: f32 = 0.95;
+const PICKUP_BLINK_TIME: f32 = 90.0;
+const RESPAWN_INVULNERABILITY: f32 = 120.0;
+const RESPAWN_BLINK_TIME: f32 = 8.0;
+const RESPAWN_CLEAR_RADIUS: f32 = 40.0;
+const LIFE_ICON_SIZE: f32 = 3.0;
+const LIFE_ICON_SPACING: f32 = 3.0;
 const SCORE_HITBOX_SIZE: f32 = 2.0;
+const MENU_WIDTH: f32 = 140.0;
+const MENU_ENTRY_HEIGHT: f32 = 12.0;
+const MENU_ENTRY_SPACING: f32 = 2.0;
+const MENU_TITLE_SPACING: f32 = 6.0;
+const VOLUME_STEP: u8 = 10;
+const SHAKE_STEP: u8 = 25;
+const MAX_SHAKE: u8 = 200;
+const MAX_LIVES: u32 = 5;
+const MAX_GAMEPADS: i32 = 4;
+const TOAST_TIME: f32 = 120.0;
+const ATTRACT_DELAY: f32 = 900.0;
+const BOT_HORIZON: u32 = 90;
+const BOT_PAD_MARGIN: f32 = 1.0;
+const BOT_OBSTACLE_MARGIN: f32 = 2.0;
+const BOT_FLY_OFFSETS: [f32; 6] = [0.0, -10.0, 10.0, -20.0, -30.0, -40.0];
+const BOT_FLY_DURATIONS: [u32; 3] = [20, 40, 70];
+const BOT_SPRINT_DURATIONS: [u32; 3] = [10, 25, 50];
+const SIMULATION_MAX_FRAMES: u64 = 60 * 60 * 30;
+const ENV_OBSERVED_OBSTACLES: usize = 4;
+const ENV_SURVIVAL_REWARD: f32 = 0.01;
+const ENV_DEATH_REWARD: f32 = -10.0;
+const TOUCH_ZONE_MIN_BAR: f32 = 60.0;
+const TOUCH_ZONE_MARGIN: f32 = 8.0;
+const TOUCH_ZONE_PRESSED_ALPHA: u8 = 80;
 
 const EFFECTIVE_PAD_FRMT: f32 = REFERENCE_FRAMERATE / PAD_MOVE_SPEED_MLT;
 const ASPECT_RATIO_H: f32 = INTERNAL_RESOLUTION.x / INTERNAL_RESOLUTION.y;
@@ -132,7 +274,15 @@ mod pad;
 mod obstacle;
 mod dither;
 mod obstacle_grid;
-mod bomb;
+mod pickup;
+mod menu;
+mod settings;
+mod input;
+mod input_source;
+mod bot;
+mod world;
+mod simulation;
+mod environment;
 
 #[derive(Clone, Copy)]
 pub struct FrameInfo {
@@ -143,191 +293,444 @@ pub struct FrameInfo {
     in_reference_frame: bool
 }
 
-#[derive(Debug)]
-struct NotPong {
-    player: Player,
-    
-    left_pad:  Pad,
-    right_pad: Pad,
+impl FrameInfo {
+    fn new(delta_time: f32, in_reference_frame: bool) -> Self {
+        let clamped_delta_time = {
+            if delta_time < 1.0 {
+                1.0
+            } else {
+                delta_time
+            }
+        };
+
+        Self {
+            delta_time,
+            clamped_delta_time,
+            tolerance: DEFAULT_TOLERANCE * clamped_delta_time,
+            in_reference_frame
+        }
+    }
+
+    /// Same frame, with time flowing `scale` times as fast
+    fn scaled(self, scale: f32) -> Self {
+        Self {
+            delta_time: self.delta_time * scale,
+            clamped_delta_time: self.clamped_delta_time * scale,
+            ..self
+        }
+    }
+}
 
-    obstacles: Vec<AnyObstacle>,
-    obstacle_grid: ObstacleGrid,
-    bomb: Option<Bomb>,
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum GameState {
+    /// The player hovers around waiting for the first jump
+    Title,
+    Playing,
+    /// Simulation is frozen, and the pause menu is shown
+    Paused,
+    /// The death explosion is playing, before going back to the title
+    GameOver,
+    Settings,
+    /// Sub-screen of the settings, where bindings are changed
+    Controls
+}
 
-    difficulty: u16,
-    last_player_count: u64,
+#[derive(Debug, Clone, Copy)]
+enum PauseEntry {
+    Resume, Settings, Restart, Quit
+}
 
-    custom_pad_count: u32,
-    sprint_amount: f32,
+impl PauseEntry {
+    const ALL: [PauseEntry; 4] = [PauseEntry::Resume, PauseEntry::Settings, PauseEntry::Restart, PauseEntry::Quit];
 
-    alpha_change: u8,
+    fn label(self) -> &'static str {
+        match self {
+            PauseEntry::Resume => "RESUME",
+            PauseEntry::Settings => "SETTINGS",
+            PauseEntry::Restart => "RESTART",
+            PauseEntry::Quit => "QUIT",
+        }
+    }
+}
 
-    sprint_cooldown: f32,
-    hit_cooldown: f32,
+#[derive(Debug, Clone, Copy)]
+enum ControlsEntry {
+    Action(Action), Reset, Back
+}
 
-    rng: ThreadRng,
-    frame_n: u64,
+impl ControlsEntry {
+    const ALL: [ControlsEntry; 6] = [
+        ControlsEntry::Action(Action::Jump), ControlsEntry::Action(Action::Sprint),
+        ControlsEntry::Action(Action::Pause), ControlsEntry::Action(Action::Restart),
+        ControlsEntry::Reset, ControlsEntry::Back
+    ];
+
+    fn label(self, bindings: &Bindings) -> String {
+        match self {
+            ControlsEntry::Action(action) => bindings.label(action),
+            ControlsEntry::Reset => String::from("RESET DEFAULTS"),
+            ControlsEntry::Back => String::from("BACK"),
+        }
+    }
+}
 
-    curr_rocket_id: u16,
+#[derive(Debug)]
+struct NotPong {
+    state: GameState,
+    /// State to go back to when leaving the settings screen
+    settings_return: GameState,
+    pause_menu: Menu,
+    settings_menu: Menu,
+    settings: Settings,
+    controls_menu: Menu,
+    /// Action waiting for a new binding to be pressed
+    rebinding: Option<Action>,
+    /// Feedback shown in place of the controls title, e.g. when a binding is already in use
+    controls_message: Option<String>,
+    /// Whether settings were changed and need to be applied and saved
+    settings_changed: bool,
+    gamepads: Gamepads,
+    device: LiveInput,
+    /// Programmatic player controlling the gameplay actions, if any. Otherwise, they come from the devices
+    player_input: Option<AnyInputSource>,
+    /// Session being recorded, along with where to save it
+    recording: Option<(PathBuf, Replay)>,
+    /// Lives given on the command line, or needed by a replay or a recording, which the settings can't change
+    fixed_lives: Option<u32>,
+    /// Amount of simulation frames run since the start of the session
+    sim_frame: u64,
+    /// Whether the bot is playing a demo, after the title screen was left alone for a while
+    attract: bool,
+    title_idle: f32,
+    /// Short notification shown at the bottom of the screen
+    toast: Option<&'static str>,
+    toast_time: f32,
+    /// Area of the window the game was drawn into on the last frame
+    screen_box: Rectangle,
+
+    world: World,
+
+    frame_n: u64
 }
 
 impl NotPong {
-    pub fn new() -> Self {
+    pub fn new(seed: u64) -> Self {
         Self {
-            left_pad: Pad::default(true),
-            right_pad: Pad::default(false),
-            player: Player::new(),
-            obstacles: Vec::new(),
-            obstacle_grid: ObstacleGrid::new(),
-            bomb: None,
-            difficulty: START_DIFFICULTY,
-            last_player_count: 0,
-            custom_pad_count: 0,
-            sprint_amount: 0.0,
-            alpha_change: ALPHA_CHANGE,
-            sprint_cooldown: SPRINT_COOLDOWN,
-            hit_cooldown: HIT_COOLDOWN,
-            rng: rand::rng(),
-            frame_n: 0,
-            curr_rocket_id: 0
+            state: GameState::Title,
+            settings_return: GameState::Title,
+            pause_menu: Menu::new(),
+            settings_menu: Menu::new(),
+            settings: Settings::load(),
+            controls_menu: Menu::new(),
+            rebinding: None,
+            controls_message: None,
+            gamepads: Gamepads::default(),
+            device: LiveInput::default(),
+            player_input: None,
+            recording: None,
+            fixed_lives: None,
+            sim_frame: 0,
+            attract: false,
+            title_idle: 0.0,
+            toast: None,
+            toast_time: 0.0,
+            settings_changed: false,
+            screen_box: Rectangle { x: 0.0, y: 0.0, width: INTERNAL_RESOLUTION.x, height: INTERNAL_RESOLUTION.y },
+            world: World::new(seed),
+            frame_n: 0
         }
     }
 
-    fn reset_part(&mut self) {
-        self.curr_rocket_id = 0;
-        self.difficulty = START_DIFFICULTY;
-        self.last_player_count = 0;
-        self.obstacles.clear();
-        self.bomb.take();
-        self.custom_pad_count = 0;
-        self.sprint_amount = 0.0;
-        self.sprint_cooldown = SPRINT_COOLDOWN;
-        self.hit_cooldown = HIT_COOLDOWN;
-        self.obstacle_grid.reset();
+    fn pause(&mut self, rocket_sounds: &HashMap<u16, SoundAlias<'_, '_>>) {
+        for sound in rocket_sounds.values() {
+            sound.pause();
+        }
+
+        // releasing the sprint key while paused would otherwise leave the player sprinting
+        self.world.sprint_off();
+        self.pause_menu.selected = 0;
+        self.state = GameState::Paused;
     }
 
-    fn player_sprint_off(&mut self) {
-        self.player.sprint_off();
-        self.alpha_change = ALPHA_CHANGE;
+    fn resume(&mut self, rocket_sounds: &HashMap<u16, SoundAlias<'_, '_>>) {
+        for sound in rocket_sounds.values() {
+            sound.resume();
+        }
+
+        self.state = GameState::Playing;
     }
 
-    fn player_sprint_on(&mut self) {
-        if self.sprint_amount > 0.0 && self.sprint_cooldown >= SPRINT_COOLDOWN {
-            self.sprint_cooldown = 0.0;
-            self.player.sprint_on();
-            self.alpha_change = SPRINT_ALPHA_CHANGE;
+    fn quit_to_title(&mut self, rocket_sounds: &mut HashMap<u16, SoundAlias<'_, '_>>) {
+        for (_, sound) in rocket_sounds.drain() {
+            sound.stop();
         }
+
+        self.end_recording();
+        self.world.quit();
+        self.state = GameState::Title;
     }
 
-    fn reset(&mut self) {
-        self.reset_part();
-        self.left_pad.reset();
-        self.right_pad.reset();
-        self.player_sprint_off();
+    fn open_settings(&mut self) {
+        self.settings_return = self.state;
+        self.settings_menu.selected = 0;
+        self.state = GameState::Settings;
     }
 
-    fn invert(&mut self, hit_sound: &Sound<'_>) {
-        if self.hit_cooldown >= HIT_COOLDOWN {
-            self.hit_cooldown = 0.0;
-            hit_sound.play();
-            self.player.invert();
+    fn apply_settings(&mut self, rl: &mut RaylibHandle, audio: &RaylibAudio) {
+        audio.set_master_volume(self.settings.volume as f32 / 100.0);
+        rl.set_target_fps(self.settings.fps_cap.target_fps());
+        self.world.set_max_lives(self.fixed_lives.unwrap_or(self.settings.lives));
+
+        if rl.is_window_fullscreen() != self.settings.fullscreen {
+            rl.toggle_fullscreen();
         }
     }
 
-    fn handle_keys(&mut self, rl: &RaylibHandle) {
-        if !self.player.sprinting && !self.player.explosion.is_alive() {
-            if rl.is_key_pressed(KeyboardKey::KEY_RIGHT) || 
-               rl.is_key_pressed(KeyboardKey::KEY_LEFT) || 
-               rl.is_key_pressed(KeyboardKey::KEY_Z) || 
-               rl.is_mouse_button_pressed(MouseButton::MOUSE_BUTTON_RIGHT) ||
-               rl.is_gesture_detected(Gesture::GESTURE_SWIPE_LEFT) ||
-               rl.is_gesture_detected(Gesture::GESTURE_SWIPE_RIGHT)
-            {
-                if self.player.playing {
-                    self.player_sprint_on();
+    fn show_toast(&mut self, text: &'static str) {
+        self.toast = Some(text);
+        self.toast_time = TOAST_TIME;
+    }
+
+    fn restart(&mut self, rocket_sounds: &mut HashMap<u16, SoundAlias<'_, '_>>) {
+        self.quit_to_title(rocket_sounds);
+        self.world.restart();
+        self.state = GameState::Playing;
+    }
+
+    fn open_controls(&mut self) {
+        self.controls_menu.selected = 0;
+        self.rebinding = None;
+        self.controls_message = None;
+        self.state = GameState::Controls;
+    }
+
+    fn handle_controls(&mut self, rl: &RaylibHandle, actions: &Actions) {
+        if let Some(action) = self.rebinding {
+            // escape always cancels, so it can't be captured
+            if rl.is_key_pressed(KeyboardKey::KEY_ESCAPE) {
+                self.rebinding = None;
+            } else if let Some(binding) = Binding::pressed(rl) {
+                self.rebinding = None;
+
+                if let Err(other) = self.settings.bindings.bind(action, binding) {
+                    self.controls_message = Some(format!("{} USED BY {}", binding.label(), other.label()));
+                } else {
+                    self.settings_changed = true;
                 }
-            } else if rl.is_key_pressed(KeyboardKey::KEY_UP) || 
-                      rl.is_key_pressed(KeyboardKey::KEY_SPACE) || 
-                      rl.is_mouse_button_pressed(MouseButton::MOUSE_BUTTON_LEFT) ||
-                      rl.is_gesture_detected(Gesture::GESTURE_TAP)
-            {
-                self.player.jump(&mut self.rng);
             }
 
             return;
         }
 
-        if self.player.playing && self.player.sprinting {
-            if rl.is_key_released(KeyboardKey::KEY_RIGHT) || 
-               rl.is_key_released(KeyboardKey::KEY_LEFT) || 
-               rl.is_key_released(KeyboardKey::KEY_Z) || 
-               rl.is_mouse_button_released(MouseButton::MOUSE_BUTTON_RIGHT) ||
-               rl.is_gesture_detected(Gesture::GESTURE_SWIPE_LEFT) ||
-               rl.is_gesture_detected(Gesture::GESTURE_SWIPE_RIGHT)
-            {
-                self.player_sprint_off();
+        if actions.get(Action::Pause).pressed {
+            self.state = GameState::Settings;
+            return;
+        }
+
+        let event = self.controls_menu.update(rl, self.screen_box, ControlsEntry::ALL.len());
+        if event.is_some() {
+            self.controls_message = None;
+        }
+
+        match event {
+            Some(MenuEvent::Activate(idx)) => {
+                match ControlsEntry::ALL[idx] {
+                    ControlsEntry::Action(action) => self.rebinding = Some(action),
+                    ControlsEntry::Reset => {
+                        self.settings.bindings = Bindings::default();
+                        self.settings_changed = true;
+                    }
+                    ControlsEntry::Back => self.state = GameState::Settings
+                }
             }
+            Some(MenuEvent::Adjust(idx, _)) => {
+                if let ControlsEntry::Action(action) = ControlsEntry::ALL[idx] {
+                    if self.settings.bindings.unbind_last(action) {
+                        self.settings_changed = true;
+                    }
+                }
+            }
+            Some(MenuEvent::Back) => self.state = GameState::Settings,
+            None => ()
         }
     }
 
-    fn new_rocket(&mut self) -> Rocket {
-        let base = rocket::Base::random(&mut self.rng);
+    fn input_frame<'a>(&'a self, rl: &'a RaylibHandle) -> InputFrame<'a> {
+        InputFrame {
+            rl: Some(rl),
+            settings: &self.settings,
+            frame: self.sim_frame,
+            game: self.world.view()
+        }
+    }
 
-        let id = self.curr_rocket_id;
-        self.curr_rocket_id = self.curr_rocket_id.wrapping_add(1);
+    /// Saves the session recorded so far and stops recording. Quitting, restarting and the demo
+    /// use the random number generator outside of the recorded frames, so a replay can't follow them
+    fn end_recording(&mut self) {
+        if let Some((path, replay)) = self.recording.take() && let Err(e) = replay.save(path) {
+            eprintln!("Could not save replay: {}", e);
+        }
+    }
 
-        let pos = {
-            match base {
-                rocket::Base::Left => {
-                    if self.rng.random_bool(0.5) && OBSTACLE_SAFE_ZONE.y < self.left_pad.pos.y {
-                        Vector2 {
-                            x: 0.0,
-                            y: self.rng.random_range(OBSTACLE_SAFE_ZONE.y ..= self.left_pad.pos.y)
-                        }
-                    } else {
-                        Vector2 {
-                            x: 0.0,
-                            y: self.rng.random_range(self.left_pad.pos.y + PAD_SIZE.y ..= INTERNAL_RESOLUTION.y - OBSTACLE_SAFE_ZONE.y)
-                        }
-                    }
+    fn start_attract(&mut self) {
+        self.end_recording();
+        self.attract = true;
+        self.player_input = Some(Bot::default().into());
+    }
+
+    fn stop_attract(&mut self, rocket_sounds: &mut HashMap<u16, SoundAlias<'_, '_>>) {
+        self.attract = false;
+        self.player_input = None;
+        self.quit_to_title(rocket_sounds);
+    }
+
+    /// Handles devices, menus, pausing and restarting. Returns the actions read from the devices
+    fn handle_keys(&mut self, rl: &RaylibHandle, rocket_sounds: &mut HashMap<u16, SoundAlias<'_, '_>>) -> Actions {
+        let mut device = mem::take(&mut self.device);
+        let actions = device.poll(&self.input_frame(rl));
+        self.device = device;
+
+        if Action::ALL.into_iter().any(|action| actions.get(action).pressed) {
+            self.title_idle = 0.0;
+
+            // any input takes control back from the demo
+            if self.attract {
+                self.stop_attract(rocket_sounds);
+                return Actions::default();
+            }
+        }
+
+        match self.gamepads.update(rl) {
+            Some(GamepadEvent::Connected) => self.show_toast(GAMEPAD_CONNECTED_TEXT),
+            Some(GamepadEvent::Disconnected) => {
+                self.show_toast(GAMEPAD_DISCONNECTED_TEXT);
+
+                // the player might not be able to control the game anymore
+                if self.state == GameState::Playing {
+                    self.pause(rocket_sounds);
+                    return actions;
                 }
-                rocket::Base::Right => {
-                    if self.rng.random_bool(0.5) && OBSTACLE_SAFE_ZONE.y < self.right_pad.pos.y {
-                        Vector2 {
-                            x: INTERNAL_RESOLUTION.x,
-                            y: self.rng.random_range(OBSTACLE_SAFE_ZONE.y ..= self.right_pad.pos.y)
-                        }
-                    } else {
-                        Vector2 {
-                            x: INTERNAL_RESOLUTION.x,
-                            y: self.rng.random_range(self.right_pad.pos.y + PAD_SIZE.y ..= INTERNAL_RESOLUTION.y - OBSTACLE_SAFE_ZONE.y)
-                        }
-                    }
+            }
+            None => ()
+        }
+
+        match self.state {
+            GameState::Title | GameState::Playing => (),
+            GameState::GameOver => return actions,
+            GameState::Paused => {
+                if actions.get(Action::Pause).pressed {
+                    self.resume(rocket_sounds);
+                    return actions;
                 }
-                rocket::Base::Top => {
-                    Vector2 {
-                        x: self.rng.random_range(OBSTACLE_SAFE_ZONE.x ..= INTERNAL_RESOLUTION.x - OBSTACLE_SAFE_ZONE.x),
-                        y: 0.0
-                    }
+
+                if actions.get(Action::Restart).pressed {
+                    self.restart(rocket_sounds);
+                    return actions;
+                }
+
+                let entry = match self.pause_menu.update(rl, self.screen_box, PauseEntry::ALL.len()) {
+                    Some(MenuEvent::Activate(idx)) => Some(PauseEntry::ALL[idx]),
+                    Some(MenuEvent::Back) => Some(PauseEntry::Resume),
+                    _ => None
+                };
+
+                match entry {
+                    Some(PauseEntry::Resume) => self.resume(rocket_sounds),
+                    Some(PauseEntry::Settings) => self.open_settings(),
+                    Some(PauseEntry::Restart) => self.restart(rocket_sounds),
+                    Some(PauseEntry::Quit) => self.quit_to_title(rocket_sounds),
+                    None => ()
+                }
+
+                return actions;
+            }
+            GameState::Settings => {
+                if actions.get(Action::Pause).pressed {
+                    self.state = self.settings_return;
+                    return actions;
                 }
-                rocket::Base::Bottom => {
-                    Vector2 {
-                        x: self.rng.random_range(OBSTACLE_SAFE_ZONE.x ..= INTERNAL_RESOLUTION.x - OBSTACLE_SAFE_ZONE.x),
-                        y: INTERNAL_RESOLUTION.y
+
+                match self.settings_menu.update(rl, self.screen_box, SettingsEntry::ALL.len()) {
+                    Some(MenuEvent::Activate(idx)) => {
+                        match SettingsEntry::ALL[idx] {
+                            SettingsEntry::Back => self.state = self.settings_return,
+                            SettingsEntry::Controls => self.open_controls(),
+                            entry => {
+                                self.settings.adjust(entry, 1);
+                                self.settings_changed = true;
+                            }
+                        }
+                    }
+                    Some(MenuEvent::Adjust(idx, delta)) => {
+                        self.settings.adjust(SettingsEntry::ALL[idx], delta);
+                        self.settings_changed = true;
                     }
+                    Some(MenuEvent::Back) => self.state = self.settings_return,
+                    None => ()
                 }
+
+                return actions;
             }
-        };
+            GameState::Controls => {
+                self.handle_controls(rl, &actions);
+                return actions;
+            }
+        }
+
+        if self.state == GameState::Playing {
+            if actions.get(Action::Pause).pressed || (!rl.is_window_focused() && !self.attract) {
+                self.pause(rocket_sounds);
+                return actions;
+            }
+
+            if actions.get(Action::Restart).pressed {
+                self.restart(rocket_sounds);
+                return actions;
+            }
+        } else if actions.get(Action::Pause).pressed {
+            self.open_settings();
+        }
 
-        Rocket::new(&mut self.rng, id, pos, base)
+        actions
     }
 
-    fn make_rocket<'a>(&mut self, rocket_sounds: &mut HashMap<u16, SoundAlias<'a, 'a>>, rocket_sound: &'a Sound<'a>) {
-        let rocket = self.new_rocket();
-        let sound = rocket_sound.alias().expect("Could not alias sound");
-        sound.play();
-        rocket_sounds.insert(rocket.id, sound);
-        self.obstacles.push(rocket.into());
+    /// Reads the gameplay actions of this simulation frame, recording them if needed.
+    /// A programmatic player may also decide the timing of the frame
+    fn poll_player(&mut self, rl: &RaylibHandle, device: Actions, frame_info: &mut FrameInfo) -> Actions {
+        let mut actions = device;
+
+        if let Some(mut source) = self.player_input.take() {
+            let polled = source.poll(&self.input_frame(rl));
+            for action in Action::GAMEPLAY {
+                actions.set(action, polled.get(action));
+            }
+
+            if let Some(timing) = source.timing() {
+                *frame_info = FrameInfo::new(timing.delta_time, timing.in_reference_frame);
+            }
+
+            self.player_input = Some(source);
+        }
+
+        if let Some((_, replay)) = &mut self.recording {
+            let timing = FrameTiming { delta_time: frame_info.delta_time, in_reference_frame: frame_info.in_reference_frame };
+            replay.push(timing, actions);
+        }
+
+        self.sim_frame += 1;
+        actions
+    }
+
+    fn handle_actions(&mut self, actions: Actions) {
+        if self.state == GameState::GameOver {
+            return;
+        }
+
+        self.world.apply(&actions);
+
+        if self.world.player.playing {
+            self.state = GameState::Playing;
+        }
     }
 
     fn run(&mut self) {
@@ -350,6 +753,13 @@ impl NotPong {
                 .expect("Could not load sound")
         ).expect("Could not load sound");
 
+        let shield_sound = audio.new_sound_from_wave(
+            &audio.new_wave_from_memory(SOUND_EXT, DEATH_SOUND)
+                .expect("Could not load sound")
+        ).expect("Could not load sound");
+
+        shield_sound.set_pitch(SHIELD_BREAK_PITCH);
+
         let bomb_sound = audio.new_sound_from_wave(
             &audio.new_wave_from_memory(SOUND_EXT, BOMB_SOUND)
                 .expect("Could not load sound")
@@ -375,15 +785,7 @@ impl NotPong {
                 .expect("Could not load texture")
         };
 
-        if !UNLIMITED_FPS {
-            rl.set_target_fps({
-                if let Some(fps) = MAX_FPS {
-                    fps
-                } else {
-                    get_monitor_refresh_rate(get_current_monitor()) as u32
-                }
-            });
-        }
+        self.apply_settings(&mut rl, &audio);
 
         rl.set_trace_log(TraceLogLevel::LOG_NONE);
 
@@ -395,281 +797,215 @@ impl NotPong {
             ).expect("Could not load render texture")
         );
 
-        self.player.init(&mut self.rng);
+        rl.set_exit_key(None);
 
         let mut rocket_sounds: HashMap<u16, SoundAlias<'_, '_>> = HashMap::new();
         let mut last_reference_frame = Instant::now();
 
         while !rl.window_should_close() {
-            let delta_time = rl.get_frame_time() * REFERENCE_FRAMERATE;
-
-            let clamped_delta_time = {
-                if delta_time < 1.0 {
-                    1.0
-                } else {
-                    delta_time
-                }
-            };
-
-            let frame_info = FrameInfo {
-                delta_time,
-                clamped_delta_time,
-                tolerance: DEFAULT_TOLERANCE * clamped_delta_time,
-                in_reference_frame: last_reference_frame.elapsed().as_secs_f32() >= REFERENCE_FRAMETIME,
-            };
+            let mut frame_info = FrameInfo::new(
+                rl.get_frame_time() * REFERENCE_FRAMERATE,
+                last_reference_frame.elapsed().as_secs_f32() >= REFERENCE_FRAMETIME
+            );
 
             if frame_info.in_reference_frame {
                 last_reference_frame = Instant::now();
             }
 
-            self.handle_keys(&rl);
-            let mut draw = rl.begin_texture_mode(&thread, get_expect_mut!(texture));
+            let device_actions = self.handle_keys(&rl, &mut rocket_sounds);
 
-            if frame_info.in_reference_frame {
-                draw.draw_rectangle(
-                    0, 0, 
-                    INTERNAL_RESOLUTION.x as i32, INTERNAL_RESOLUTION.y as i32,  
-                    Color { r: BG.r, g: BG.g, b: BG.b, a: self.alpha_change }
-                );
-            }
+            if self.settings_changed {
+                self.settings_changed = false;
+                self.apply_settings(&mut rl, &audio);
 
-            if self.player.is_dead(&self.left_pad, &self.right_pad, frame_info.tolerance, &mut self.rng) {
-                for (_, sound) in rocket_sounds.drain() {
-                    sound.stop();
+                if let Err(e) = self.settings.save() {
+                    eprintln!("Could not save settings: {}", e);
                 }
-
-                death_sound.play();
-                self.reset();
             }
 
-            if self.player.playing {
-                draw.draw_text(
-                    &self.player.count.to_string(), 
-                    (INTERNAL_RESOLUTION.x / 2.0) as i32, (INTERNAL_RESOLUTION.y / 2.0) as i32, 
-                    SCORE_TEXT_HEIGHT,
-                    FG
-                );
-                
-                draw.draw_rectangle(
-                    SPRINT_LINE_POS.x as i32, 
-                    SPRINT_LINE_POS.y as i32, 
-                    (SPRINT_LINE_MIN_LENGTH + self.sprint_amount * SPRINT_LINE_MAX_LENGTH / SPRINT_MAX_VALUE) as i32, 
-                    SPRINT_LINE_WIDTH as i32, 
-                    FG
-                );
-
-                draw.draw_texture(
-                    &lightning,
-                    LIGHTNING_POS.x as i32,
-                    LIGHTNING_POS.y as i32,
-                    Color::WHITE
-                );
-
-                self.left_pad.update(delta_time, &mut draw);
-                self.right_pad.update(delta_time, &mut draw);
-
-                if self.left_pad.move_if_collides(self.player.pos, frame_info.tolerance, &mut self.rng) || 
-                   self.right_pad.move_if_collides(self.player.pos, frame_info.tolerance, &mut self.rng) 
-                {
-                    self.invert(&hit_sound);
-                }
-
-                if let Some(bomb) = &mut self.bomb {
-                    if bomb.is_alive() {
-                        bomb.update(delta_time, &mut draw);
-
-                        if bomb.to_destroy.is_empty() && bomb.collides(self.player.pos, vec2(PLAYER_SIZE, PLAYER_SIZE)) {
-                            bomb_sound.play();
-
-                            // select `amount` random rocks' positions to destroy them
-                            let amount = self.rng.random_range(BOMB_MIN_DESTROYED_OBSTACLES..=BOMB_MAX_DESTROYED_OBSTACLES);
-                            let mut to_destroy: Vec<Rectangle> = self.obstacles.iter()
-                                .filter(|x| matches!(x, AnyObstacle::Rock(_)))
-                                .choose_multiple(&mut self.rng, amount)
-                                .into_iter()
-                                .map(|obstacle| {
-                                    let pos = obstacle.pos();
-                                    let size = obstacle.size();
-                                    Rectangle { x: pos.x, y: pos.y, width: size.x, height: size.y }
-                                })
-                                .collect();
-                            
-                            // if there are no obstacles to destroy, the bomb will give points instead
-                            if to_destroy.is_empty() {
-                                bomb.give_points = amount as u64;
-                                bomb.to_destroy.push(Rectangle { 
-                                    x: INTERNAL_RESOLUTION.x / 2.0 - SCORE_HITBOX_SIZE / 2.0, 
-                                    y: INTERNAL_RESOLUTION.y / 2.0 - SCORE_HITBOX_SIZE / 2.0, 
-                                    width:  SCORE_HITBOX_SIZE, 
-                                    height: SCORE_HITBOX_SIZE
-                                });
-                            } else {
-                                bomb.to_destroy.append(&mut to_destroy);
+            // the texture is left untouched behind menus, so the game looks frozen
+            let frozen = matches!(self.state, GameState::Paused | GameState::Settings | GameState::Controls);
+
+            if !frozen {
+                let actions = self.poll_player(&rl, device_actions, &mut frame_info);
+                self.handle_actions(actions);
+                self.world.step(frame_info);
+
+                for event in self.world.events.drain(..) {
+                    match event {
+                        WorldEvent::Hit => hit_sound.play(),
+                        WorldEvent::ShieldBroken => shield_sound.play(),
+                        WorldEvent::LifeLost(_) => death_sound.play(),
+                        WorldEvent::Death { .. } => {
+                            for (_, sound) in rocket_sounds.drain() {
+                                sound.stop();
                             }
+
+                            death_sound.play();
+                            self.state = GameState::GameOver;
                         }
-                    } else {
-                        if bomb.give_points > 0 {
-                            pew_sound.play();
-                            self.player.count += bomb.give_points;
-                            let mut explosion = Explosion::new(bomb.pos);
-                            explosion.explode(OBSTACLE_COLLISION_MAX_VELOCITY, true, &mut self.rng);
-                            self.obstacles.push(ObstacleExplosion(explosion).into());
+                        WorldEvent::PickupSpawned(_) => (),
+                        WorldEvent::PickupCollected(_) => bomb_sound.play(),
+                        WorldEvent::BombPoints(_) | WorldEvent::ObstacleDestroyed => pew_sound.play(),
+                        WorldEvent::RocketLaunched(id) => {
+                            let sound = rocket_sound.alias().expect("Could not alias sound");
+                            sound.play();
+                            rocket_sounds.insert(id, sound);
+                        }
+                        WorldEvent::RocketGone(id) => {
+                            rocket_sounds.remove(&id).expect("Rocket sound wasn't in map").stop();
                         }
-
-                        self.bomb.take();
-                    }
-                } else if self.player.count >= PLAYER_COUNT_BOMB {
-                    if frame_info.in_reference_frame {
-                        let probability = (BOMB_PROBABILITY as f32 / clamped_delta_time).round() as u16;
-                        if self.rng.random_range(0..=probability) < 1 {
-                            // TODO: we should probably avoid the possibility of spawning the bomb directly on the player, 
-                            //       but it's an advantage for them so it's fine for now
-                            self.bomb = Some(Bomb::new(Vector2 { 
-                                x: self.rng.random_range(OBSTACLE_SAFE_ZONE.x ..= INTERNAL_RESOLUTION.x - OBSTACLE_SAFE_ZONE.x), 
-                                y: self.rng.random_range(OBSTACLE_SAFE_ZONE.y ..= INTERNAL_RESOLUTION.y - OBSTACLE_SAFE_ZONE.y), 
-                            }));
-                        } 
                     }
                 }
 
-                if self.player.count >= PLAYER_COUNT_OBST {
-                    if self.player.count != self.last_player_count && self.player.count % MOD_INCREMENT_DIFF == 0 {
-                        self.last_player_count = self.player.count;
-                        self.difficulty += 1;
-                    }
+                for obstacle in &self.world.obstacles {
+                    let (id, pos) = match obstacle {
+                        AnyObstacle::Rocket(rocket) => (rocket.id, rocket.pos),
+                        AnyObstacle::Missile(missile) => (missile.id, missile.pos),
+                        _ => continue
+                    };
 
-                    if frame_info.in_reference_frame {
-                        if ROCKETS_TEST {
-                            self.make_rocket(&mut rocket_sounds, &rocket_sound);
-                        } else {
-                            let probability = (OBSTACLE_PROBABILITY as f32 / clamped_delta_time).round() as u16;
-                            if self.rng.random_bool(0.5) {
-                                if self.rng.random_range(0..=probability) < self.difficulty {
-                                    if let Some((id, pos)) = self.obstacle_grid.alloc(self.player.pos, &mut self.rng) {
-                                        self.obstacles.push(Rock::new(&mut self.rng, id, pos).into());
-                                    } else if ROCKETS { // if you can't allocate a rock, make a rocket instead
-                                        self.make_rocket(&mut rocket_sounds, &rocket_sound);
-                                    }
-                                }
-                            } else {
-                                if ROCKETS {
-                                    if self.rng.random_range(0..=probability) < self.difficulty {
-                                        self.make_rocket(&mut rocket_sounds, &rocket_sound);
-                                    }
-                                }
-                            }
-                        }
-                    }
+                    let sound = rocket_sounds.get(&id).unwrap();
+                    sound.set_pan(1.0 - pos.x / INTERNAL_RESOLUTION.x);
+                    sound.set_pitch(if self.world.effects.slow_motion() { SLOW_MOTION_PITCH } else { 1.0 });
                 }
+            }
 
-                for i in 0 .. self.obstacles.len() {
-                    self.obstacles[i].update(frame_info, &mut self.rng, &mut draw);
+            if self.state == GameState::Title && self.player_input.is_none() {
+                self.title_idle += frame_info.delta_time;
+                if self.title_idle >= ATTRACT_DELAY {
+                    self.start_attract();
+                }
+            } else {
+                self.title_idle = 0.0;
+            }
 
-                    if !self.obstacles[i].can_collide() {
-                        continue;
-                    }
+            let mut draw = rl.begin_texture_mode(&thread, get_expect_mut!(texture));
 
-                    if let Some(bomb) = &mut self.bomb {
-                        if self.obstacles[i].collides_object(bomb.pos, vec2(BOMB_SIZE, BOMB_SIZE)) {
-                            pew_sound.play();
-                            self.obstacles[i].kill();
-                            let mut explosion = Explosion::new(self.obstacles[i].pos());
-                            explosion.explode(OBSTACLE_COLLISION_MAX_VELOCITY, true, &mut self.rng);
-                            self.obstacles.push(ObstacleExplosion(explosion).into());
-                            continue;
-                        }
+            if self.state == GameState::GameOver && !self.world.player.explosion.is_alive() {
+                self.state = GameState::Title;
+            }
+
+            if !frozen {
+                if frame_info.in_reference_frame {
+                    let alpha_change = if self.world.player.sprinting { SPRINT_ALPHA_CHANGE } else { ALPHA_CHANGE };
+
+                    draw.draw_rectangle(
+                        0, 0, 
+                        INTERNAL_RESOLUTION.x as i32, INTERNAL_RESOLUTION.y as i32,  
+                        Color { r: BG.r, g: BG.g, b: BG.b, a: alpha_change }
+                    );
+                }
+
+                if self.state == GameState::Playing {
+                    draw.draw_text(
+                        &self.world.player.count.to_string(), 
+                        (INTERNAL_RESOLUTION.x / 2.0) as i32, (INTERNAL_RESOLUTION.y / 2.0) as i32, 
+                        SCORE_TEXT_HEIGHT,
+                        FG
+                    );
+
+                    if self.attract {
+                        let size = draw.measure_text(ATTRACT_TEXT, INTRO_TEXT_HEIGHT);
+                        draw.draw_text(
+                            ATTRACT_TEXT, 
+                            INTERNAL_RESOLUTION.x as i32 / 2 - size / 2, 
+                            INTERNAL_RESOLUTION.y as i32 / 2 - INTRO_TEXT_HEIGHT / 2 - INTRO_TEXT_Y_OFFSET, 
+                            INTRO_TEXT_HEIGHT, 
+                            FG
+                        );
                     }
-                    
-                    if !NOCLIP {
-                        if self.obstacles[i].collides_object(self.player.pos, vec2(PLAYER_SIZE, PLAYER_SIZE)) {
-                            self.player.dead = true;
-                            break;
+                
+                    draw.draw_rectangle(
+                        SPRINT_LINE_POS.x as i32, 
+                        SPRINT_LINE_POS.y as i32, 
+                        (SPRINT_LINE_MIN_LENGTH + self.world.sprint_amount * SPRINT_LINE_MAX_LENGTH / SPRINT_MAX_VALUE) as i32, 
+                        SPRINT_LINE_WIDTH as i32, 
+                        FG
+                    );
+
+                    draw.draw_texture(
+                        &lightning,
+                        LIGHTNING_POS.x as i32,
+                        LIGHTNING_POS.y as i32,
+                        Color::WHITE
+                    );
+
+                    if self.world.max_lives() > 1 {
+                        for i in 0 .. self.world.lives {
+                            draw.draw_rectangle(
+                                (INTERNAL_RESOLUTION.x - LIVES_RPOS.x - (i + 1) as f32 * (LIFE_ICON_SIZE + LIFE_ICON_SPACING) + LIFE_ICON_SPACING) as i32, 
+                                LIVES_RPOS.y as i32, 
+                                LIFE_ICON_SIZE as i32, LIFE_ICON_SIZE as i32, 
+                                FG
+                            );
                         }
                     }
+                }
 
-                    if let AnyObstacle::Rocket(rocket) = &self.obstacles[i] {
-                        rocket_sounds.get(&rocket.id).unwrap()
-                            .set_pan(1.0 - rocket.pos.x / INTERNAL_RESOLUTION.x);
-                    }
+                if self.state == GameState::Title {
+                    let size = draw.measure_text(INTRO_TEXT, INTRO_TEXT_HEIGHT);
+                    draw.draw_text(
+                        INTRO_TEXT, 
+                        INTERNAL_RESOLUTION.x as i32 / 2 - size / 2, 
+                        INTERNAL_RESOLUTION.y as i32 / 2 - INTRO_TEXT_HEIGHT / 2 - INTRO_TEXT_Y_OFFSET, 
+                        INTRO_TEXT_HEIGHT, 
+                        FG
+                    );
+                }
 
-                    for j in 0 .. self.obstacles.len() {
-                        if j == i || !self.obstacles[j].can_collide() {
-                            continue;
-                        }
+                self.world.draw(&mut draw);
+            }
 
-                        let collides = {
-                            if i < j {
-                                let (l, r) = self.obstacles.split_at_mut(j);
-                                l[i].collides_other(&r[0])
-                            } else {
-                                let (l, r) = self.obstacles.split_at_mut(i);
-                                l[j].collides_other(&r[0])
-                            }
-                        };
-
-                        if collides {
-                            pew_sound.play();
-                            self.obstacles[i].kill();
-                            self.obstacles[j].kill();
-                            let mut explosion = Explosion::new(self.obstacles[i].pos());
-                            explosion.explode(OBSTACLE_COLLISION_MAX_VELOCITY, true, &mut self.rng);
-                            self.obstacles.push(ObstacleExplosion(explosion).into());
+            match self.state {
+                GameState::Paused => {
+                    let entries = PauseEntry::ALL.map(PauseEntry::label);
+                    self.pause_menu.draw(&mut draw, PAUSE_TEXT, &entries);
+                }
+                GameState::Settings => {
+                    let entries = SettingsEntry::ALL.map(|entry| self.settings.label(entry));
+                    self.settings_menu.draw(&mut draw, SETTINGS_TEXT, &entries);
+                }
+                GameState::Controls => {
+                    let title = {
+                        if let Some(action) = self.rebinding {
+                            format!("PRESS {} (ESC: CANCEL)", action.label())
+                        } else if let Some(message) = &self.controls_message {
+                            message.clone()
+                        } else {
+                            String::from(CONTROLS_TEXT)
                         }
-                    }
+                    };
+
+                    let entries = ControlsEntry::ALL.map(|entry| entry.label(&self.settings.bindings));
+                    self.controls_menu.draw(&mut draw, &title, &entries);
                 }
+                _ => ()
+            }
 
-                self.obstacles.retain_mut(|obstacle| {
-                    if obstacle.is_alive() {
-                        return true;
-                    }
+            if let Some(toast) = self.toast {
+                let size = draw.measure_text(toast, TOAST_TEXT_HEIGHT);
+                let pos_x = INTERNAL_RESOLUTION.x as i32 / 2 - size / 2;
+                let pos_y = INTERNAL_RESOLUTION.y as i32 - TOAST_Y_OFFSET;
 
-                    match obstacle {
-                        AnyObstacle::Rock(rock) => {
-                            self.obstacle_grid.free(rock.id).expect("Same ID was freed twice");
-                        }
-                        AnyObstacle::Rocket(rocket) => {
-                            rocket_sounds.remove(&rocket.id).expect("Rocket sound wasn't in map").stop();
-                        }
-                        _ => ()
-                    }
-                    
-                    false
-                });
-
-                if self.player.sprinting {                    
-                    if self.sprint_amount > 0.0 {
-                        self.sprint_amount -= SPRINT_USE_DELTA * delta_time;
-                    } else {
-                        self.player_sprint_off();
-                    }
+                draw.draw_rectangle(pos_x - 1, pos_y - 1, size + 2, TOAST_TEXT_HEIGHT + 2, BG);
+
+                if self.toast_time > 0.0 {
+                    draw.draw_text(toast, pos_x, pos_y, TOAST_TEXT_HEIGHT, FG);
+                    self.toast_time -= frame_info.delta_time;
                 } else {
-                    if self.sprint_amount < SPRINT_MAX_VALUE {
-                        self.sprint_amount += SPRINT_CHARGE_DELTA * delta_time;
-                    }
+                    // the background was drawn one last time to clear the text from frozen screens
+                    self.toast = None;
                 }
-
-                self.sprint_cooldown += delta_time;
-                self.hit_cooldown += delta_time;
-            } else if !self.player.explosion.is_alive() {
-                let size = draw.measure_text(INTRO_TEXT, INTRO_TEXT_HEIGHT);
-                draw.draw_text(
-                    INTRO_TEXT, 
-                    INTERNAL_RESOLUTION.x as i32 / 2 - size / 2, 
-                    INTERNAL_RESOLUTION.y as i32 / 2 - INTRO_TEXT_HEIGHT / 2 - INTRO_TEXT_Y_OFFSET, 
-                    INTRO_TEXT_HEIGHT, 
-                    FG
-                );
             }
 
-            self.player.update(frame_info, &mut self.rng, &mut draw);
-
             drop(draw);
 
             let raw_texture = texture.take().unwrap().to_raw();
             let mut image = unsafe { Image::from_raw(raylib::ffi::LoadImageFromTexture(raw_texture.texture)) };
             texture.set(unsafe { RenderTexture2D::from_raw(raw_texture) }).unwrap();
 
-            dither::apply(&mut image);
+            dither::apply(&mut image, self.settings.dithering, self.settings.theme);
             let postprocessed = rl.load_texture_from_image(&thread, &image)
                 .expect("Couldn't load postprocessed image");
 
@@ -698,39 +1034,159 @@ impl NotPong {
             };
 
             let bounding_box = destination;
+            self.screen_box = bounding_box;
 
-            if self.player.sprinting {
-                destination.x += self.rng.random_range(-SHAKE..=SHAKE);
-                destination.y += self.rng.random_range(-SHAKE..=SHAKE);
+            let shake = SHAKE * self.settings.shake as f32 / 100.0;
+            if self.world.player.sprinting && shake > 0.0 {
+                // not taken from the game's generator, so the shake setting doesn't affect replays
+                destination.x += rand::rng().random_range(-shake..=shake);
+                destination.y += rand::rng().random_range(-shake..=shake);
             }
             
             let mut draw = rl.begin_drawing(&thread);
-            draw.clear_background(Color::BLACK);
+            draw.clear_background(self.settings.theme.bg());
             // draws the texture flipped upside down (coordinate system is y-flipped in texture mode)
             draw.draw_texture_pro( 
                 &postprocessed, 
                 Rectangle { x: 0.0, y: 0.0, width: INTERNAL_RESOLUTION.x, height: -INTERNAL_RESOLUTION.y }, 
                 destination, 
                 Vector2 { x: 0.0, y: 0.0 }, 
-                0.0, Color::WHITE
+                0.0, if self.world.effects.slow_motion() { SLOW_MOTION_TINT } else { Color::WHITE }
             );
 
             // game box
             draw.draw_rectangle_lines(
                 bounding_box.x as i32, bounding_box.y as i32, 
                 bounding_box.width as i32 + 1, bounding_box.height as i32 + 1, 
-                Color::BLUEVIOLET
+                self.settings.theme.border()
             );
 
-            if SHOW_FPS {
+            let (width, height) = (draw.get_screen_width() as f32, draw.get_screen_height() as f32);
+            self.device.touch_zones.draw(&mut draw, self.settings.touch, bounding_box, width, height, self.settings.theme.border());
+
+            if self.settings.show_fps {
                 draw.draw_fps(0, 0);
             }
 
             self.frame_n = self.frame_n.wrapping_add(1);
         }
+
+        self.end_recording();
     }
 }
 
+fn exit_with_error(msg: &str) -> ! {
+    eprintln!("{}", msg);
+    process::exit(1)
+}
+
 fn main() {
-    NotPong::new().run()
+    let mut seed = None;
+    let mut record = None;
+    let mut player_input = None;
+    let mut simulate = None;
+    let mut seed_range = None;
+    let mut format = OutputFormat::Csv;
+    let mut output = None;
+    let mut env = false;
+    let mut lives = None;
+    let mut replay_lives = None;
+    let mut player_inputs = 0;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().unwrap_or_else(|| exit_with_error(&format!("Missing value for {}", arg)));
+
+        match arg.as_str() {
+            "--seed" => {
+                seed = Some(value().parse().unwrap_or_else(|_| exit_with_error("Invalid seed")));
+            }
+            "--record" => record = Some(PathBuf::from(value())),
+            "--replay" => {
+                let replay = Replay::load(value())
+                    .unwrap_or_else(|e| exit_with_error(&format!("Could not load replay: {}", e)));
+
+                seed = Some(replay.seed);
+                replay_lives = Some(replay.lives);
+                player_input = Some(ReplayInput::new(replay).into());
+                player_inputs += 1;
+            }
+            "--lives" => {
+                lives = Some(value().parse().ok().filter(|x| *x > 0).unwrap_or_else(|| exit_with_error("Invalid lives count")));
+            }
+            "--bot" => {
+                player_input = Some(Bot::default().into());
+                player_inputs += 1;
+            }
+            "--script" => {
+                let script = ScriptedInput::load(value())
+                    .unwrap_or_else(|e| exit_with_error(&format!("Could not load script: {}", e)));
+
+                player_input = Some(script.into());
+                player_inputs += 1;
+            }
+            "--simulate" => {
+                simulate = Some(value().parse().unwrap_or_else(|_| exit_with_error("Invalid game count")));
+            }
+            "--seed-range" => {
+                let range = value().split_once("..")
+                    .and_then(|(a, b)| Some(a.parse().ok()? .. b.parse().ok()?))
+                    .filter(|range: &Range<u64>| !range.is_empty())
+                    .unwrap_or_else(|| exit_with_error("Invalid seed range, expected a..b"));
+
+                seed_range = Some(range);
+            }
+            "--format" => {
+                format = OutputFormat::parse(&value()).unwrap_or_else(|| exit_with_error("Invalid format, expected csv or json"));
+            }
+            "--output" => output = Some(PathBuf::from(value())),
+            "--env" => env = true,
+            _ => exit_with_error(&format!("Unknown argument: {}", arg))
+        }
+    }
+
+    if player_inputs > 1 {
+        exit_with_error("Only one of --replay, --bot and --script can be used at once");
+    }
+
+    if lives.is_some() && replay_lives.is_some() {
+        exit_with_error("--lives can't be used with --replay, which plays with the recorded lives");
+    }
+
+    let lives = replay_lives.or(lives);
+
+    if env {
+        if let Err(e) = environment::serve() {
+            exit_with_error(&format!("Environment protocol error: {}", e));
+        }
+
+        return;
+    }
+
+    if let Some(count) = simulate {
+        let policy = player_input.unwrap_or_else(|| Bot::default().into());
+        let games = simulation::simulate(count, seed_range.unwrap_or(0 .. count.max(1)), lives.unwrap_or(1), &policy);
+        let summary = Summary::new(&games);
+
+        let contents = match format {
+            OutputFormat::Csv => simulation::to_csv(&games),
+            OutputFormat::Json => simulation::to_json(&games, &summary)
+        };
+
+        match output {
+            Some(path) => fs::write(path, contents).unwrap_or_else(|e| exit_with_error(&format!("Could not write output: {}", e))),
+            None => print!("{}", contents)
+        }
+
+        eprintln!("{}", summary.report());
+        return;
+    }
+
+    let seed = seed.unwrap_or_else(rand::random);
+
+    let mut game = NotPong::new(seed);
+    game.player_input = player_input;
+    game.fixed_lives = lives.or(record.is_some().then_some(game.settings.lives));
+    game.recording = record.map(|path| (path, Replay::new(seed, game.fixed_lives.unwrap_or(1))));
+    game.run()
 }
\ No newline at end of file
@@ -0,0 +1,175 @@
+/*
+Copyright (C) 2025 Amari Calipso
+
+This file is part of !pong.
+
+!pong is free software: you can redistribute it and/or modify
+it under the terms of the GNU Affero General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+!pong is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Affero General Public License for more details.
+
+You should have received a copy of the GNU Affero General Public License
+along with !pong.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use raylib::{ffi::{GamepadButton, Gesture, KeyboardKey, MouseButton}, math::{Rectangle, Vector2}, prelude::RaylibDraw, RaylibHandle};
+
+use crate::{input::gamepad_button_pressed, utils::{point_in_rect, to_internal}, BG, FG, INTERNAL_RESOLUTION, MENU_ENTRY_HEIGHT, MENU_ENTRY_SPACING, MENU_TEXT_HEIGHT, MENU_TITLE_HEIGHT, MENU_TITLE_SPACING, MENU_WIDTH};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MenuEvent {
+    /// The entry at the given index was clicked or confirmed
+    Activate(usize),
+    /// The value of the entry at the given index should be changed by the given amount of steps
+    Adjust(usize, i32),
+    /// The menu should be closed
+    Back
+}
+
+#[derive(Debug)]
+pub struct Menu {
+    pub selected: usize,
+    last_pointer: Vector2
+}
+
+impl Menu {
+    pub fn new() -> Self {
+        Self {
+            selected: 0,
+            last_pointer: Vector2::zero()
+        }
+    }
+
+    fn start_y(len: usize) -> f32 {
+        let height = MENU_TITLE_HEIGHT as f32 + MENU_TITLE_SPACING +
+                     len as f32 * (MENU_ENTRY_HEIGHT + MENU_ENTRY_SPACING) - MENU_ENTRY_SPACING;
+
+        INTERNAL_RESOLUTION.y / 2.0 - height / 2.0
+    }
+
+    fn entry_rect(idx: usize, len: usize) -> Rectangle {
+        Rectangle {
+            x: INTERNAL_RESOLUTION.x / 2.0 - MENU_WIDTH / 2.0,
+            y: Self::start_y(len) + MENU_TITLE_HEIGHT as f32 + MENU_TITLE_SPACING + idx as f32 * (MENU_ENTRY_HEIGHT + MENU_ENTRY_SPACING),
+            width: MENU_WIDTH,
+            height: MENU_ENTRY_HEIGHT
+        }
+    }
+
+    /// Handles navigation and returns what happened to the entries this frame, if anything.
+    /// `screen` is the area of the window the game is drawn into, used to map the pointer to the internal resolution
+    pub fn update(&mut self, rl: &RaylibHandle, screen: Rectangle, len: usize) -> Option<MenuEvent> {
+        if len == 0 {
+            return None;
+        }
+
+        if rl.is_key_pressed(KeyboardKey::KEY_UP) || rl.is_key_pressed(KeyboardKey::KEY_W) ||
+           gamepad_button_pressed(rl, GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_UP)
+        {
+            self.selected = (self.selected + len - 1) % len;
+        } else if rl.is_key_pressed(KeyboardKey::KEY_DOWN) || rl.is_key_pressed(KeyboardKey::KEY_S) ||
+                  gamepad_button_pressed(rl, GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_DOWN)
+        {
+            self.selected = (self.selected + 1) % len;
+        }
+
+        let pointer = to_internal(rl.get_mouse_position(), screen);
+        let clicked = rl.is_mouse_button_pressed(MouseButton::MOUSE_BUTTON_LEFT) ||
+                      rl.is_gesture_detected(Gesture::GESTURE_TAP);
+        let right_clicked = rl.is_mouse_button_pressed(MouseButton::MOUSE_BUTTON_RIGHT);
+
+        // only let the pointer change the selection when it moves, so it doesn't fight with the keyboard
+        let moved = pointer != self.last_pointer;
+        self.last_pointer = pointer;
+
+        for i in 0 .. len {
+            if point_in_rect(pointer, Self::entry_rect(i, len)) {
+                if moved || clicked || right_clicked {
+                    self.selected = i;
+                }
+
+                if clicked {
+                    return Some(MenuEvent::Activate(i));
+                } else if right_clicked {
+                    return Some(MenuEvent::Adjust(i, -1));
+                }
+            }
+        }
+
+        if rl.is_key_pressed(KeyboardKey::KEY_ENTER) || rl.is_key_pressed(KeyboardKey::KEY_SPACE) ||
+           gamepad_button_pressed(rl, GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_DOWN)
+        {
+            return Some(MenuEvent::Activate(self.selected));
+        } else if rl.is_key_pressed(KeyboardKey::KEY_RIGHT) || rl.is_key_pressed(KeyboardKey::KEY_D) ||
+                  gamepad_button_pressed(rl, GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_RIGHT)
+        {
+            return Some(MenuEvent::Adjust(self.selected, 1));
+        } else if rl.is_key_pressed(KeyboardKey::KEY_LEFT) || rl.is_key_pressed(KeyboardKey::KEY_A) ||
+                  gamepad_button_pressed(rl, GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_LEFT)
+        {
+            return Some(MenuEvent::Adjust(self.selected, -1));
+        } else if rl.is_key_pressed(KeyboardKey::KEY_BACKSPACE) ||
+                  gamepad_button_pressed(rl, GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_RIGHT)
+        {
+            return Some(MenuEvent::Back);
+        }
+
+        None
+    }
+
+    pub fn draw(&self, draw: &mut (impl RaylibDraw + std::ops::Deref<Target = RaylibHandle>), title: &str, entries: &[impl AsRef<str>]) {
+        let start_y = Self::start_y(entries.len());
+        let title_size = draw.measure_text(title, MENU_TITLE_HEIGHT);
+        let width = (MENU_WIDTH as i32).max(title_size);
+
+        // background, so the frozen game doesn't bleed through the text
+        draw.draw_rectangle(
+            INTERNAL_RESOLUTION.x as i32 / 2 - width / 2 - MENU_ENTRY_SPACING as i32,
+            start_y as i32 - MENU_ENTRY_SPACING as i32,
+            width + MENU_ENTRY_SPACING as i32 * 2,
+            (Self::entry_rect(entries.len() - 1, entries.len()).y + MENU_ENTRY_HEIGHT - start_y) as i32 + MENU_ENTRY_SPACING as i32 * 2,
+            BG
+        );
+
+        draw.draw_text(
+            title,
+            INTERNAL_RESOLUTION.x as i32 / 2 - title_size / 2,
+            start_y as i32,
+            MENU_TITLE_HEIGHT,
+            FG
+        );
+
+        for (i, entry) in entries.iter().enumerate() {
+            let rect = Self::entry_rect(i, entries.len());
+            let text = entry.as_ref();
+
+            let color = {
+                if i == self.selected {
+                    draw.draw_rectangle(
+                        rect.x as i32, rect.y as i32,
+                        rect.width as i32, rect.height as i32,
+                        FG
+                    );
+
+                    BG
+                } else {
+                    FG
+                }
+            };
+
+            let size = draw.measure_text(text, MENU_TEXT_HEIGHT);
+            draw.draw_text(
+                text,
+                (rect.x + rect.width / 2.0) as i32 - size / 2,
+                (rect.y + rect.height / 2.0) as i32 - MENU_TEXT_HEIGHT / 2,
+                MENU_TEXT_HEIGHT,
+                color
+            );
+        }
+    }
+}
\ No newline at end of file
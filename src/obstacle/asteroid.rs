@@ -0,0 +1,215 @@
+/*
+Copyright (C) 2025 Amari Calipso
+
+This file is part of !pong.
+
+!pong is free software: you can redistribute it and/or modify
+it under the terms of the GNU Affero General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+!pong is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Affero General Public License for more details.
+
+You should have received a copy of the GNU Affero General Public License
+along with !pong.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+
+use std::f32::consts::TAU;
+
+use rand::{rngs::StdRng, Rng};
+use raylib::{color::Color, math::Vector2, prelude::RaylibDraw};
+
+use crate::{obstacle::{gravity_well::{total_force, Field}, rect_collides_other, AnyObstacle, Obstacle}, obstacle_grid::ObstacleGrid, utils::{rects_overlap, square_collides, vec2}, FrameInfo, ASTEROID_MAX_FRAGMENTS, ASTEROID_MAX_SPEED, ASTEROID_MIN_FRAGMENTS, ASTEROID_MIN_SPEED, ASTEROID_SIZE, ASTEROID_SPLIT_GRACE, ASTEROID_SPLIT_RATIO, ASTEROID_SPLIT_SPEED, FG, GRAVITY_WELL_CORE, GRAVITY_WELL_ROCK_PULL, INTERNAL_RESOLUTION, MIN_OBSTACLE_SIZE, OBSTACLE_DELTA_ALPHA, OBSTACLE_START_ALPHA};
+
+/// Drifting rock that breaks into smaller ones when a rocket or a bomb hits it
+#[derive(Debug)]
+pub struct Asteroid {
+    /// Cell of the obstacle grid it appeared in, until it drifts away from it. Fragments don't have one
+    pub id: Option<u16>,
+
+    pos: Vector2,
+    size: Vector2,
+    /// Movement per reference frame
+    velocity: Vector2,
+    /// Frames left before a fragment can hit other obstacles, so it can fly away from what split it
+    grace: f32,
+
+    alpha: u8
+}
+
+impl Asteroid {
+    pub fn new(rng: &mut StdRng, id: u16, pos: Vector2) -> Self {
+        let size = vec2(ASTEROID_SIZE, ASTEROID_SIZE);
+        let angle = rng.random_range(0.0 .. TAU);
+        let speed = rng.random_range(ASTEROID_MIN_SPEED ..= ASTEROID_MAX_SPEED);
+
+        Self {
+            id: Some(id),
+            pos: pos - size,
+            size,
+            velocity: vec2(angle.cos(), angle.sin()) * speed,
+            grace: 0.0,
+            alpha: OBSTACLE_START_ALPHA
+        }
+    }
+
+    /// Whether it's a fragment that just split off
+    pub fn is_fresh(&self) -> bool {
+        self.grace > 0.0
+    }
+
+    /// Releases its grid cell once it's out of the area obstacles spawned there can cover
+    pub fn leave_cell(&mut self, grid: &mut ObstacleGrid) {
+        let Some(id) = self.id else {
+            return;
+        };
+
+        if grid.area(id).is_some_and(|(pos, size)| !rects_overlap(self.pos, self.size, pos, size)) {
+            grid.free(id).expect("Same ID was freed twice");
+            self.id = None;
+        }
+    }
+
+    /// Fragments flying apart from the center, or none if it's already as small as it gets
+    pub fn split(&self, rng: &mut StdRng) -> Vec<Asteroid> {
+        let size = self.size * ASTEROID_SPLIT_RATIO;
+        if size.x < MIN_OBSTACLE_SIZE {
+            return Vec::new();
+        }
+
+        let center = self.pos + self.size / 2.0;
+        let count = rng.random_range(ASTEROID_MIN_FRAGMENTS ..= ASTEROID_MAX_FRAGMENTS);
+        let start = rng.random_range(0.0 .. TAU);
+
+        (0 .. count)
+            .map(|i| {
+                let angle = start + TAU * i as f32 / count as f32;
+                let direction = vec2(angle.cos(), angle.sin());
+
+                Asteroid {
+                    id: None,
+                    pos: center - size / 2.0 + direction * (size.x / 2.0),
+                    size,
+                    velocity: self.velocity + direction * ASTEROID_SPLIT_SPEED,
+                    grace: ASTEROID_SPLIT_GRACE,
+                    alpha: self.alpha
+                }
+            })
+            .collect()
+    }
+}
+
+impl Obstacle for Asteroid {
+    fn can_collide(&self) -> bool {
+        self.is_alive()
+    }
+
+    fn hits_obstacles(&self) -> bool {
+        !self.is_fresh()
+    }
+
+    fn pos(&self) -> Vector2 {
+        self.pos
+    }
+
+    fn size(&self) -> Vector2 {
+        self.size
+    }
+
+    fn velocity(&self) -> Vector2 {
+        self.velocity
+    }
+
+    fn pull(&mut self, fields: &[Field]) {
+        let center = self.pos + self.size / 2.0;
+
+        if fields.iter().any(|field| field.center.distance_to(center) < GRAVITY_WELL_CORE) {
+            self.kill();
+        } else {
+            self.velocity += total_force(fields, center) * GRAVITY_WELL_ROCK_PULL;
+        }
+    }
+
+    fn kill(&mut self) {
+        self.alpha = 0;
+    }
+
+    fn is_alive(&self) -> bool {
+        self.alpha > 0 &&
+        self.pos.x + self.size.x > 0.0 && self.pos.x < INTERNAL_RESOLUTION.x &&
+        self.pos.y + self.size.y > 0.0 && self.pos.y < INTERNAL_RESOLUTION.y
+    }
+
+    fn update(&mut self, frame_info: FrameInfo, _rng: &mut StdRng) {
+        self.pos += self.velocity * frame_info.delta_time;
+        self.grace -= frame_info.delta_time;
+        self.alpha = self.alpha.saturating_add_signed(OBSTACLE_DELTA_ALPHA);
+    }
+
+    fn draw(&self, draw: &mut impl RaylibDraw) {
+        let color = Color { r: FG.r, g: FG.g, b: FG.b, a: self.alpha };
+
+        draw.draw_rectangle_lines(
+            self.pos.x as i32, self.pos.y as i32, 
+            self.size.x as i32, self.size.y as i32, 
+            color
+        );
+
+        draw.draw_rectangle(
+            (self.pos.x + self.size.x / 4.0) as i32, (self.pos.y + self.size.y / 4.0) as i32, 
+            (self.size.x / 2.0) as i32, (self.size.y / 2.0) as i32, 
+            color
+        );
+    }
+
+    fn collides_object(&mut self, pos: Vector2, size: Vector2) -> bool {
+        square_collides(self.pos, self.size, pos, size)
+    }
+
+    fn collides_other(&mut self, other: &AnyObstacle) -> bool {
+        rect_collides_other(self.pos, self.size, other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use super::*;
+
+    const SEEDS: u64 = 200;
+
+    #[test]
+    fn asteroids_hold_their_cell_only_while_on_it() {
+        for seed in 0 .. SEEDS {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let mut grid = ObstacleGrid::new();
+
+            let (id, pos) = grid.alloc(&[], &mut rng).unwrap();
+            let (area_pos, area_size) = grid.area(id).unwrap();
+            let mut asteroid = Asteroid::new(&mut rng, id, pos);
+
+            while asteroid.is_alive() {
+                asteroid.leave_cell(&mut grid);
+
+                let on_cell = rects_overlap(asteroid.pos, asteroid.size, area_pos, area_size);
+                assert_eq!(asteroid.id.is_some(), on_cell, "asteroid at {:?}, cell area at {:?}", asteroid.pos, area_pos);
+
+                if !on_cell {
+                    // given back to the grid, so the next obstacle can take it
+                    assert_eq!(grid.area(id), None);
+                    assert_eq!(grid.free(id), Err(()));
+                    break;
+                }
+
+                asteroid.update(FrameInfo::new(1.0, true), &mut rng);
+            }
+
+            assert_eq!(asteroid.id, None, "asteroid left the screen without leaving its cell");
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,207 @@
+/*
+Copyright (C) 2025 Amari Calipso
+
+This file is part of !pong.
+
+!pong is free software: you can redistribute it and/or modify
+it under the terms of the GNU Affero General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+!pong is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Affero General Public License for more details.
+
+You should have received a copy of the GNU Affero General Public License
+along with !pong.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+
+use rand::rngs::StdRng;
+use raylib::{color::Color, math::Vector2, prelude::RaylibDraw};
+
+use crate::{obstacle::{AnyObstacle, Obstacle}, obstacle_grid::ObstacleGrid, utils::{rects_overlap, vec2}, FrameInfo, BARRIER_LENGTH, BARRIER_LIFE, BARRIER_PAUSE, BARRIER_SPEED, BARRIER_WIDTH, FG, OBSTACLE_DELTA_ALPHA, OBSTACLE_START_ALPHA};
+
+/// Wall segment sliding back and forth between two neighbouring cells of the obstacle grid, holding the cells it's in.
+/// Like other obstacles, it sits up and left of the point of the cell it's in
+#[derive(Debug)]
+pub struct Barrier {
+    ends: [Vector2; 2],
+    /// Grid cells it holds, one per end
+    cells: [Option<u16>; 2],
+    /// Position between the two ends, from 0 to 1
+    progress: f32,
+    /// End it's moving to, or waiting at
+    target: usize,
+    /// Frames left before it tries to move on
+    pause: f32,
+
+    lifespan: f32,
+    step: i8,
+    alpha: u8
+}
+
+impl Barrier {
+    pub fn new(id: u16, start: Vector2, end: Vector2) -> Self {
+        Self {
+            ends: [start, end],
+            cells: [Some(id), None],
+            progress: 0.0,
+            target: 0,
+            pause: BARRIER_PAUSE,
+            lifespan: BARRIER_LIFE,
+            step: OBSTACLE_DELTA_ALPHA,
+            alpha: OBSTACLE_START_ALPHA
+        }
+    }
+
+    fn is_horizontal(&self) -> bool {
+        self.ends[0].y == self.ends[1].y
+    }
+
+    fn arrived(&self) -> bool {
+        self.progress == self.target as f32
+    }
+
+    /// Ids of the grid cells it's holding
+    pub fn cells(&self) -> impl Iterator<Item = u16> + '_ {
+        self.cells.iter().flatten().copied()
+    }
+
+    /// Releases the cell it left once it reaches an end, then claims the other one before moving back toward it.
+    /// Waits while that cell is taken by another obstacle
+    pub fn occupy(&mut self, grid: &mut ObstacleGrid) {
+        if !self.arrived() {
+            return;
+        }
+
+        let other = 1 - self.target;
+        if let Some(id) = self.cells[other].take() {
+            grid.free(id).expect("Same ID was freed twice");
+        }
+
+        if self.pause <= 0.0 && self.lifespan > 0.0 && let Some(id) = grid.claim(self.ends[other]) {
+            self.cells[other] = Some(id);
+            self.target = other;
+        }
+    }
+}
+
+impl Obstacle for Barrier {
+    fn pos(&self) -> Vector2 {
+        self.ends[0] + (self.ends[1] - self.ends[0]) * self.progress - self.size()
+    }
+
+    fn size(&self) -> Vector2 {
+        if self.is_horizontal() {
+            vec2(BARRIER_LENGTH, BARRIER_WIDTH)
+        } else {
+            vec2(BARRIER_WIDTH, BARRIER_LENGTH)
+        }
+    }
+
+    fn velocity(&self) -> Vector2 {
+        if self.arrived() {
+            Vector2::zero()
+        } else {
+            (self.ends[self.target] - self.ends[1 - self.target]).normalized() * BARRIER_SPEED
+        }
+    }
+
+    fn kill(&mut self) {
+        self.alpha = 0;
+    }
+
+    fn is_alive(&self) -> bool {
+        self.alpha > 0
+    }
+
+    fn update(&mut self, frame_info: FrameInfo, _rng: &mut StdRng) {
+        if self.lifespan <= 0.0 {
+            self.step = (-(OBSTACLE_DELTA_ALPHA as f32) * frame_info.delta_time) as i8;
+        }
+
+        self.lifespan -= frame_info.delta_time;
+        self.alpha = self.alpha.saturating_add_signed(self.step);
+
+        if self.arrived() {
+            self.pause -= frame_info.delta_time;
+            return;
+        }
+
+        let delta = BARRIER_SPEED * frame_info.delta_time / self.ends[0].distance_to(self.ends[1]);
+        if self.target == 1 {
+            self.progress = (self.progress + delta).min(1.0);
+        } else {
+            self.progress = (self.progress - delta).max(0.0);
+        }
+
+        if self.arrived() {
+            self.pause = BARRIER_PAUSE;
+        }
+    }
+
+    fn draw(&self, draw: &mut impl RaylibDraw) {
+        let pos = self.pos();
+        let size = self.size();
+
+        draw.draw_rectangle(
+            pos.x as i32, pos.y as i32, 
+            size.x as i32, size.y as i32, 
+            Color { r: FG.r, g: FG.g, b: FG.b, a: self.alpha }
+        );
+    }
+
+    fn collides_object(&mut self, pos: Vector2, size: Vector2) -> bool {
+        rects_overlap(self.pos(), self.size(), pos, size)
+    }
+
+    fn collides_other(&mut self, other: &AnyObstacle) -> bool {
+        self.shape().overlaps(&other.shape())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use crate::obstacle::rock::Rock;
+
+    use super::*;
+
+    const SEEDS: u64 = 200;
+
+    /// Fills every cell left in `grid` with a rock, checking that none of them touches `barrier`
+    fn check_rocks(grid: &mut ObstacleGrid, barrier: &Barrier, rng: &mut StdRng) {
+        while let Some((id, pos)) = grid.alloc(&[], rng) {
+            let rock = Rock::new(rng, id, pos);
+            assert!(
+                !rects_overlap(barrier.pos(), barrier.size(), rock.pos(), rock.size()),
+                "barrier at {:?} overlaps a rock at {:?}", barrier.pos(), rock.pos()
+            );
+        }
+    }
+
+    #[test]
+    fn barriers_stay_clear_of_other_cells() {
+        for seed in 0 .. SEEDS {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let mut grid = ObstacleGrid::new();
+
+            let (id, start, end) = grid.alloc_pair(&[], &mut rng).unwrap();
+            let mut barrier = Barrier::new(id, start, end);
+
+            // waiting at the start, with the other end free
+            check_rocks(&mut grid, &barrier, &mut rng);
+
+            // waiting at the other end, once it left the start
+            grid.reset();
+            let id = grid.claim(end).unwrap();
+            barrier.cells = [None, Some(id)];
+            barrier.progress = 1.0;
+            barrier.target = 1;
+            check_rocks(&mut grid, &barrier, &mut rng);
+        }
+    }
+}
\ No newline at end of file
@@ -17,10 +17,10 @@ You should have received a copy of the GNU Affero General Public License
 along with !pong.  If not, see <http://www.gnu.org/licenses/>.
  */
 
-use rand::rngs::ThreadRng;
+use rand::rngs::StdRng;
 use raylib::{math::Vector2, prelude::RaylibDraw};
 
-use crate::{explosion::Explosion, obstacle::{AnyObstacle, Obstacle}, FrameInfo};
+use crate::{explosion::Explosion, obstacle::{gravity_well::Field, AnyObstacle, Obstacle}, FrameInfo};
 
 #[derive(Debug)]
 pub struct ObstacleExplosion(pub Explosion);
@@ -41,8 +41,15 @@ impl Obstacle for ObstacleExplosion {
         self.0.is_alive()
     }
 
-    fn update(&mut self, frame_info: FrameInfo, _rng: &mut ThreadRng, draw: &mut impl RaylibDraw) {
+    fn pull(&mut self, fields: &[Field]) {
+        self.0.pull(fields);
+    }
+
+    fn update(&mut self, frame_info: FrameInfo, _rng: &mut StdRng) {
         self.0.update(frame_info);
+    }
+
+    fn draw(&self, draw: &mut impl RaylibDraw) {
         self.0.show(draw);
     }
 
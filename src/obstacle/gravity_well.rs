@@ -0,0 +1,159 @@
+/*
+Copyright (C) 2025 Amari Calipso
+
+This file is part of !pong.
+
+!pong is free software: you can redistribute it and/or modify
+it under the terms of the GNU Affero General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+!pong is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Affero General Public License for more details.
+
+You should have received a copy of the GNU Affero General Public License
+along with !pong.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+
+use rand::rngs::StdRng;
+use raylib::{color::Color, math::Vector2, prelude::RaylibDraw};
+
+use crate::{explosion::Explosion, obstacle::{AnyObstacle, Obstacle}, utils::square_collides, FrameInfo, BG, FG, GRAVITY_WELL_COLLAPSE_TIME, GRAVITY_WELL_CORE, GRAVITY_WELL_LIFE, GRAVITY_WELL_RADIUS, GRAVITY_WELL_RING_SPEED, GRAVITY_WELL_STRENGTH, OBSTACLE_COLLISION_MAX_VELOCITY};
+
+/// Pull of a gravity well on what's around it
+#[derive(Debug, Clone, Copy)]
+pub struct Field {
+    pub center: Vector2,
+    /// Acceleration right next to the center, fading out toward `GRAVITY_WELL_RADIUS`
+    pub strength: f32
+}
+
+impl Field {
+    pub fn force_at(&self, pos: Vector2) -> Vector2 {
+        let offset = self.center - pos;
+        let distance = offset.length();
+
+        if distance == 0.0 || distance >= GRAVITY_WELL_RADIUS {
+            return Vector2::zero();
+        }
+
+        offset / distance * self.strength * (1.0 - distance / GRAVITY_WELL_RADIUS)
+    }
+}
+
+/// Sum of the pulls of all `fields` at `pos`
+pub fn total_force(fields: &[Field], pos: Vector2) -> Vector2 {
+    fields.iter().fold(Vector2::zero(), |acc, field| acc + field.force_at(pos))
+}
+
+/// Black hole pulling the player, rocks and particles toward it, until it collapses
+#[derive(Debug)]
+pub struct GravityWell {
+    center: Vector2,
+    /// Frames since it appeared
+    time: f32,
+    collapsed: bool,
+    burst: Explosion,
+
+    dead: bool
+}
+
+impl GravityWell {
+    pub fn new(center: Vector2) -> Self {
+        Self {
+            center,
+            time: 0.0,
+            collapsed: false,
+            burst: Explosion::new(center),
+            dead: false
+        }
+    }
+
+    /// How much of its strength is left, shrinking to zero while it collapses
+    fn scale(&self) -> f32 {
+        (1.0 - (self.time - GRAVITY_WELL_LIFE).max(0.0) / GRAVITY_WELL_COLLAPSE_TIME).max(0.0)
+    }
+
+    pub fn field(&self) -> Option<Field> {
+        if self.collapsed || self.dead {
+            return None;
+        }
+
+        Some(Field {
+            center: self.center,
+            strength: GRAVITY_WELL_STRENGTH * self.scale()
+        })
+    }
+}
+
+impl Obstacle for GravityWell {
+    fn can_collide(&self) -> bool {
+        !self.collapsed
+    }
+
+    /// Rocks get swallowed when they're pulled in, see `Rock::pull`
+    fn hits_obstacles(&self) -> bool {
+        false
+    }
+
+    fn pos(&self) -> Vector2 {
+        self.center - Vector2::one() * GRAVITY_WELL_CORE
+    }
+
+    fn size(&self) -> Vector2 {
+        Vector2::one() * GRAVITY_WELL_CORE * 2.0
+    }
+
+    fn kill(&mut self) {
+        self.dead = true;
+    }
+
+    fn is_alive(&self) -> bool {
+        !self.dead && (!self.collapsed || self.burst.is_alive())
+    }
+
+    fn update(&mut self, frame_info: FrameInfo, rng: &mut StdRng) {
+        self.time += frame_info.delta_time;
+
+        if !self.collapsed && self.time >= GRAVITY_WELL_LIFE + GRAVITY_WELL_COLLAPSE_TIME {
+            self.collapsed = true;
+            self.burst.explode(OBSTACLE_COLLISION_MAX_VELOCITY, false, rng);
+        }
+
+        self.burst.update(frame_info);
+    }
+
+    fn draw(&self, draw: &mut impl RaylibDraw) {
+        if self.collapsed {
+            self.burst.show(draw);
+            return;
+        }
+
+        let scale = self.scale();
+        let (x, y) = (self.center.x as i32, self.center.y as i32);
+
+        // rings falling into the core
+        let span = GRAVITY_WELL_RADIUS - GRAVITY_WELL_CORE;
+        for i in 0 .. 2 {
+            let progress = (self.time * GRAVITY_WELL_RING_SPEED / span + i as f32 / 2.0).fract();
+            let radius = GRAVITY_WELL_CORE + span * (1.0 - progress) * scale;
+            let alpha = (progress * 128.0 * scale) as u8;
+
+            draw.draw_circle_lines(x, y, radius, Color { r: FG.r, g: FG.g, b: FG.b, a: alpha });
+        }
+
+        draw.draw_circle(x, y, GRAVITY_WELL_CORE * scale, BG);
+        draw.draw_circle_lines(x, y, GRAVITY_WELL_CORE * scale, FG);
+    }
+
+    fn collides_object(&mut self, pos: Vector2, size: Vector2) -> bool {
+        square_collides(self.pos(), self.size(), pos, size)
+    }
+
+    fn collides_other(&mut self, _other: &AnyObstacle) -> bool {
+        false
+    }
+}
\ No newline at end of file
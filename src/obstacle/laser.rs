@@ -0,0 +1,126 @@
+/*
+Copyright (C) 2025 Amari Calipso
+
+This file is part of !pong.
+
+!pong is free software: you can redistribute it and/or modify
+it under the terms of the GNU Affero General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+!pong is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Affero General Public License for more details.
+
+You should have received a copy of the GNU Affero General Public License
+along with !pong.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+
+use rand::rngs::StdRng;
+use raylib::{math::Vector2, prelude::RaylibDraw};
+
+use crate::{obstacle::{AnyObstacle, Obstacle}, utils::{rects_overlap, vec2}, FrameInfo, FG, INTERNAL_RESOLUTION, LASER_FIRE_TIME, LASER_FLICKER_TIME, LASER_WARNING_TIME, LASER_WIDTH};
+
+/// Beam crossing the whole arena, announced by a flickering warning line
+#[derive(Debug)]
+pub struct Laser {
+    /// Whether the beam goes from left to right, rather than from top to bottom
+    horizontal: bool,
+    /// Position of the beam's top or left edge
+    offset: f32,
+    /// Frames since it appeared
+    time: f32,
+
+    dead: bool
+}
+
+impl Laser {
+    pub fn new(horizontal: bool, offset: f32) -> Self {
+        Self {
+            horizontal, offset,
+            time: 0.0,
+            dead: false
+        }
+    }
+
+    fn is_firing(&self) -> bool {
+        self.time >= LASER_WARNING_TIME
+    }
+
+    /// Whether the beam will be on after `frames` more frames
+    pub fn is_firing_after(&self, frames: f32) -> bool {
+        let time = self.time + frames;
+        !self.dead && (LASER_WARNING_TIME .. LASER_WARNING_TIME + LASER_FIRE_TIME).contains(&time)
+    }
+}
+
+impl Obstacle for Laser {
+    /// The warning line is harmless
+    fn can_collide(&self) -> bool {
+        self.is_firing()
+    }
+
+    fn hits_obstacles(&self) -> bool {
+        false
+    }
+
+    fn pos(&self) -> Vector2 {
+        if self.horizontal {
+            vec2(0.0, self.offset)
+        } else {
+            vec2(self.offset, 0.0)
+        }
+    }
+
+    fn size(&self) -> Vector2 {
+        if self.horizontal {
+            vec2(INTERNAL_RESOLUTION.x, LASER_WIDTH)
+        } else {
+            vec2(LASER_WIDTH, INTERNAL_RESOLUTION.y)
+        }
+    }
+
+    fn kill(&mut self) {
+        self.dead = true;
+    }
+
+    fn is_alive(&self) -> bool {
+        !self.dead && self.time < LASER_WARNING_TIME + LASER_FIRE_TIME
+    }
+
+    fn update(&mut self, frame_info: FrameInfo, _rng: &mut StdRng) {
+        self.time += frame_info.delta_time;
+    }
+
+    fn draw(&self, draw: &mut impl RaylibDraw) {
+        let pos = self.pos();
+        let size = self.size();
+
+        if self.is_firing() {
+            draw.draw_rectangle(
+                pos.x as i32, pos.y as i32, 
+                size.x as i32, size.y as i32, 
+                FG
+            );
+        } else if (self.time / LASER_FLICKER_TIME) as i32 % 2 == 0 {
+            // one pixel wide, along the middle of the beam
+            if self.horizontal {
+                let y = (self.offset + LASER_WIDTH / 2.0) as i32;
+                draw.draw_line(0, y, INTERNAL_RESOLUTION.x as i32, y, FG);
+            } else {
+                let x = (self.offset + LASER_WIDTH / 2.0) as i32;
+                draw.draw_line(x, 0, x, INTERNAL_RESOLUTION.y as i32, FG);
+            }
+        }
+    }
+
+    fn collides_object(&mut self, pos: Vector2, size: Vector2) -> bool {
+        rects_overlap(self.pos(), self.size(), pos, size)
+    }
+
+    fn collides_other(&mut self, _other: &AnyObstacle) -> bool {
+        false
+    }
+}
\ No newline at end of file
@@ -0,0 +1,176 @@
+/*
+Copyright (C) 2025 Amari Calipso
+
+This file is part of !pong.
+
+!pong is free software: you can redistribute it and/or modify
+it under the terms of the GNU Affero General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+!pong is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Affero General Public License for more details.
+
+You should have received a copy of the GNU Affero General Public License
+along with !pong.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+
+use rand::rngs::StdRng;
+use raylib::{color::Color, math::Vector2, prelude::RaylibDraw};
+
+use crate::{explosion::Explosion, obstacle::{AnyObstacle, Obstacle, Shape}, FrameInfo, BG, FG, MINE_ARM_TIME, MINE_BLAST_RADIUS, MINE_BLAST_VELOCITY, MINE_FAST_PULSE, MINE_FUSE_TIME, MINE_LIFE, MINE_RADIUS, MINE_SENSE_RADIUS, MINE_SLOW_PULSE, MINE_TRIGGER_RADIUS, OBSTACLE_DELTA_ALPHA, OBSTACLE_START_ALPHA};
+
+/// Fades in like a rock, then blows up when the player gets too close, taking everything around it along
+#[derive(Debug)]
+pub struct Mine {
+    pub id: u16,
+
+    center: Vector2,
+    /// Frames since it appeared
+    time: f32,
+    /// Blinking of the light, in cycles
+    pulse: f32,
+    target_distance: f32,
+    /// Frames left before the blast, once it's triggered
+    fuse: Option<f32>,
+
+    step: i8,
+    alpha: u8,
+
+    blast: Explosion,
+    detonated: bool,
+    /// Whether the world still has to apply the blast
+    blast_pending: bool
+}
+
+impl Mine {
+    pub fn new(id: u16, pos: Vector2) -> Self {
+        let center = pos - Vector2::one() * MINE_RADIUS;
+
+        Self {
+            id, center,
+            time: 0.0,
+            pulse: 0.0,
+            target_distance: f32::INFINITY,
+            fuse: None,
+            step: OBSTACLE_DELTA_ALPHA,
+            alpha: OBSTACLE_START_ALPHA,
+            blast: Explosion::new(center),
+            detonated: false,
+            blast_pending: false
+        }
+    }
+
+    pub fn center(&self) -> Vector2 {
+        self.center
+    }
+
+    pub fn is_armed(&self) -> bool {
+        !self.detonated && self.time >= MINE_ARM_TIME && self.step > 0
+    }
+
+    /// Center of the blast, only on the frame the mine went off
+    pub fn take_blast(&mut self) -> Option<Vector2> {
+        if self.blast_pending {
+            self.blast_pending = false;
+            Some(self.center)
+        } else {
+            None
+        }
+    }
+
+    fn detonate(&mut self, rng: &mut StdRng) {
+        self.detonated = true;
+        self.blast_pending = true;
+        self.blast.explode(MINE_BLAST_VELOCITY, false, rng);
+    }
+}
+
+impl Obstacle for Mine {
+    fn can_collide(&self) -> bool {
+        !self.detonated
+    }
+
+    fn pos(&self) -> Vector2 {
+        self.center - Vector2::one() * MINE_RADIUS
+    }
+
+    fn size(&self) -> Vector2 {
+        Vector2::one() * MINE_RADIUS * 2.0
+    }
+
+    fn shape(&self) -> Shape {
+        Shape::Circle { center: self.center, radius: MINE_RADIUS }
+    }
+
+    fn steer(&mut self, target: Vector2) {
+        self.target_distance = self.center.distance_to(target);
+    }
+
+    fn kill(&mut self) {
+        self.alpha = 0;
+    }
+
+    fn is_alive(&self) -> bool {
+        self.alpha > 0 && (!self.detonated || self.blast.is_alive())
+    }
+
+    fn update(&mut self, frame_info: FrameInfo, rng: &mut StdRng) {
+        if self.detonated {
+            self.blast.update(frame_info);
+            return;
+        }
+
+        self.time += frame_info.delta_time;
+        if self.time >= MINE_LIFE && self.fuse.is_none() {
+            self.step = (-(OBSTACLE_DELTA_ALPHA as f32) * frame_info.delta_time) as i8;
+        }
+
+        self.alpha = self.alpha.saturating_add_signed(self.step);
+
+        if let Some(fuse) = self.fuse.as_mut() {
+            *fuse -= frame_info.delta_time;
+            self.pulse += MINE_FAST_PULSE * 2.0 * frame_info.delta_time;
+
+            if *fuse <= 0.0 {
+                self.detonate(rng);
+            }
+        } else if self.is_armed() {
+            let proximity = 1.0 - (self.target_distance / MINE_SENSE_RADIUS).min(1.0);
+            self.pulse += (MINE_SLOW_PULSE + (MINE_FAST_PULSE - MINE_SLOW_PULSE) * proximity) * frame_info.delta_time;
+
+            if self.target_distance <= MINE_TRIGGER_RADIUS {
+                self.fuse = Some(MINE_FUSE_TIME);
+            }
+        }
+    }
+
+    fn draw(&self, draw: &mut impl RaylibDraw) {
+        if self.detonated {
+            self.blast.show(draw);
+            return;
+        }
+
+        let (x, y) = (self.center.x as i32, self.center.y as i32);
+        draw.draw_circle(x, y, MINE_RADIUS, Color { r: FG.r, g: FG.g, b: FG.b, a: self.alpha });
+
+        if self.fuse.is_some() {
+            draw.draw_circle_lines(x, y, MINE_BLAST_RADIUS, Color { r: FG.r, g: FG.g, b: FG.b, a: 96 });
+        }
+
+        if self.is_armed() && self.pulse.fract() < 0.5 {
+            draw.draw_circle(x, y, MINE_RADIUS / 2.0, BG);
+        }
+    }
+
+    fn collides_object(&mut self, pos: Vector2, size: Vector2) -> bool {
+        self.shape().overlaps_rect(pos, size)
+    }
+
+    fn collides_other(&mut self, other: &AnyObstacle) -> bool {
+        self.shape().overlaps(&other.shape())
+    }
+}
\ No newline at end of file
@@ -0,0 +1,136 @@
+/*
+Copyright (C) 2025 Amari Calipso
+
+This file is part of !pong.
+
+!pong is free software: you can redistribute it and/or modify
+it under the terms of the GNU Affero General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+!pong is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Affero General Public License for more details.
+
+You should have received a copy of the GNU Affero General Public License
+along with !pong.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+
+use std::f32::consts::{PI, TAU};
+
+use rand::rngs::StdRng;
+use raylib::{math::Vector2, prelude::RaylibDraw};
+
+use crate::{explosion::Explosion, obstacle::{gravity_well::Field, rect_collides_other, AnyObstacle, Obstacle}, utils::{square_collides, vec2}, FrameInfo, FG, INTERNAL_RESOLUTION, MISSILE_FUEL, MISSILE_SIZE, MISSILE_SMOKE_COLOR, MISSILE_SMOKE_VELOCITY, MISSILE_SPEED, MISSILE_TURN_RATE};
+
+/// Rocket that steers toward the player until it runs out of fuel, leaving a smoke trail
+#[derive(Debug)]
+pub struct Missile {
+    pub id: u16,
+
+    pub pos: Vector2,
+    /// Direction of flight, in radians
+    angle: f32,
+    /// Frames left during which the missile can steer
+    fuel: f32,
+    /// Where it's steering to, updated every frame
+    target: Vector2,
+
+    smoke: Explosion,
+    dead: bool
+}
+
+impl Missile {
+    /// `angle` is the initial direction of flight, in radians
+    pub fn new(id: u16, pos: Vector2, angle: f32) -> Self {
+        Self {
+            id, pos, angle,
+            fuel: MISSILE_FUEL,
+            target: pos,
+            smoke: Explosion::new(pos),
+            dead: false
+        }
+    }
+
+    fn center(&self) -> Vector2 {
+        self.pos + Vector2::one() * (MISSILE_SIZE / 2.0)
+    }
+
+    fn direction(&self) -> Vector2 {
+        vec2(self.angle.cos(), self.angle.sin())
+    }
+}
+
+impl Obstacle for Missile {
+    fn pos(&self) -> Vector2 {
+        self.pos
+    }
+
+    fn size(&self) -> Vector2 {
+        vec2(MISSILE_SIZE, MISSILE_SIZE)
+    }
+
+    fn velocity(&self) -> Vector2 {
+        self.direction() * MISSILE_SPEED
+    }
+
+    fn steer(&mut self, target: Vector2) {
+        self.target = target;
+    }
+
+    fn pull(&mut self, fields: &[Field]) {
+        self.smoke.pull(fields);
+    }
+
+    fn kill(&mut self) {
+        self.dead = true;
+    }
+
+    fn is_alive(&self) -> bool {
+        // it starts right outside the screen when launched from the right or the bottom
+        !self.dead && 
+        self.pos.x > -MISSILE_SIZE * 2.0 && self.pos.x < INTERNAL_RESOLUTION.x + MISSILE_SIZE && 
+        self.pos.y > -MISSILE_SIZE * 2.0 && self.pos.y < INTERNAL_RESOLUTION.y + MISSILE_SIZE
+    }
+
+    fn update(&mut self, frame_info: FrameInfo, rng: &mut StdRng) {
+        if self.fuel > 0.0 {
+            self.fuel -= frame_info.delta_time;
+
+            let to_target = self.target - self.center();
+            let diff = (to_target.y.atan2(to_target.x) - self.angle + PI).rem_euclid(TAU) - PI;
+            let max_turn = MISSILE_TURN_RATE * frame_info.delta_time;
+            self.angle += diff.clamp(-max_turn, max_turn);
+
+            if frame_info.in_reference_frame {
+                let tail = self.center() - self.direction() * (MISSILE_SIZE / 2.0);
+                self.smoke.emit(tail, MISSILE_SMOKE_VELOCITY, MISSILE_SMOKE_COLOR, rng);
+            }
+        }
+
+        self.pos += self.velocity() * frame_info.delta_time;
+        self.smoke.update(frame_info);
+    }
+
+    fn draw(&self, draw: &mut impl RaylibDraw) {
+        self.smoke.show(draw);
+
+        let center = self.center();
+        let direction = self.direction() * MISSILE_SIZE;
+        let side = vec2(-direction.y, direction.x) / 2.0;
+        let back = center - direction / 2.0;
+
+        // counter clockwise whatever the direction, as the vertices rotate together
+        draw.draw_triangle(back - side, back + side, center + direction, FG);
+    }
+
+    fn collides_object(&mut self, pos: Vector2, size: Vector2) -> bool {
+        square_collides(self.pos, self.size(), pos, size)
+    }
+
+    fn collides_other(&mut self, other: &AnyObstacle) -> bool {
+        rect_collides_other(self.pos, self.size(), other)
+    }
+}
\ No newline at end of file
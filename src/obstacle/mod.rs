@@ -18,26 +18,87 @@ along with !pong.  If not, see <http://www.gnu.org/licenses/>.
  */
 
 use enum_dispatch::enum_dispatch;
-use rand::rngs::ThreadRng;
+use rand::rngs::StdRng;
 use raylib::{math::Vector2, prelude::RaylibDraw};
 
-use crate::{obstacle::{explosion::ObstacleExplosion, rock::Rock, rocket::Rocket}, FrameInfo};
+use crate::{obstacle::{asteroid::Asteroid, barrier::Barrier, explosion::ObstacleExplosion, gravity_well::{Field, GravityWell}, laser::Laser, mine::Mine, missile::Missile, rock::Rock, rocket::Rocket}, utils::{circle_overlaps_rect, rects_overlap, square_collides}, FrameInfo};
 
 pub mod rock;
+pub mod asteroid;
+pub mod barrier;
 pub mod rocket;
+pub mod missile;
+pub mod laser;
+pub mod gravity_well;
+pub mod mine;
 pub mod explosion;
 
+/// Area covered by an obstacle, for collisions that aren't between two rectangles
+#[derive(Debug, Clone, Copy)]
+pub enum Shape {
+    Rect { pos: Vector2, size: Vector2 },
+    Circle { center: Vector2, radius: f32 }
+}
+
+impl Shape {
+    pub fn overlaps_rect(&self, pos: Vector2, size: Vector2) -> bool {
+        match *self {
+            Shape::Rect { pos: own_pos, size: own_size } => rects_overlap(own_pos, own_size, pos, size),
+            Shape::Circle { center, radius } => circle_overlaps_rect(center, radius, pos, size)
+        }
+    }
+
+    pub fn overlaps(&self, other: &Shape) -> bool {
+        match (*self, *other) {
+            (Shape::Circle { center, radius }, Shape::Circle { center: other_center, radius: other_radius }) => {
+                center.distance_to(other_center) < radius + other_radius
+            }
+            (_, Shape::Rect { pos, size }) => self.overlaps_rect(pos, size),
+            (Shape::Rect { pos, size }, circle) => circle.overlaps_rect(pos, size)
+        }
+    }
+}
+
+/// Collision between a rectangular obstacle and any other one
+pub fn rect_collides_other(pos: Vector2, size: Vector2, other: &AnyObstacle) -> bool {
+    match other.shape() {
+        Shape::Rect { pos: other_pos, size: other_size } => square_collides(pos, size, other_pos, other_size),
+        circle => circle.overlaps_rect(pos, size)
+    }
+}
+
 #[enum_dispatch(AnyObstacle)]
 pub trait Obstacle {
     fn can_collide(&self) -> bool {
         true
     }
 
+    /// Whether it destroys, and gets destroyed by, the other obstacles it touches
+    fn hits_obstacles(&self) -> bool {
+        true
+    }
+
     fn pos(&self) -> Vector2;
     fn size(&self) -> Vector2;
 
+    fn shape(&self) -> Shape {
+        Shape::Rect { pos: self.pos(), size: self.size() }
+    }
+
+    /// Movement per reference frame
+    fn velocity(&self) -> Vector2 {
+        Vector2::zero()
+    }
+
+    /// Tells the obstacle where the player is, on every frame before updating it
+    fn steer(&mut self, _target: Vector2) {}
+
+    /// Lets gravity wells pull the obstacle, once per reference frame
+    fn pull(&mut self, _fields: &[Field]) {}
+
     fn is_alive(&self) -> bool;
-    fn update(&mut self, frame_info: FrameInfo, rng: &mut ThreadRng, draw: &mut impl RaylibDraw);
+    fn update(&mut self, frame_info: FrameInfo, rng: &mut StdRng);
+    fn draw(&self, draw: &mut impl RaylibDraw);
     
     fn kill(&mut self);
     fn collides_object(&mut self, pos: Vector2, size: Vector2) -> bool;
@@ -48,6 +109,12 @@ pub trait Obstacle {
 #[derive(Debug)]
 pub enum AnyObstacle {
     Rock,
+    Asteroid,
+    Barrier,
     Rocket,
+    Missile,
+    Laser,
+    GravityWell,
+    Mine,
     ObstacleExplosion
 }
@@ -17,10 +17,10 @@ You should have received a copy of the GNU Affero General Public License
 along with !pong.  If not, see <http://www.gnu.org/licenses/>.
  */
 
-use rand::{rngs::ThreadRng, Rng};
+use rand::{rngs::StdRng, Rng};
 use raylib::{color::Color, math::Vector2, prelude::RaylibDraw};
 
-use crate::{obstacle::{AnyObstacle, Obstacle}, utils::{square_collides, vec2}, FrameInfo, FG, MAX_OBSTACLE_LIFE, MAX_OBSTACLE_SIZE, MIN_OBSTACLE_LIFE, MIN_OBSTACLE_SIZE, OBSTACLE_DELTA_ALPHA, OBSTACLE_START_ALPHA};
+use crate::{obstacle::{gravity_well::{total_force, Field}, rect_collides_other, AnyObstacle, Obstacle}, utils::{square_collides, vec2}, FrameInfo, FG, GRAVITY_WELL_CORE, GRAVITY_WELL_ROCK_PULL, MAX_OBSTACLE_LIFE, MAX_OBSTACLE_SIZE, MIN_OBSTACLE_LIFE, MIN_OBSTACLE_SIZE, OBSTACLE_DELTA_ALPHA, OBSTACLE_START_ALPHA};
 
 #[derive(Debug)]
 pub struct Rock {
@@ -28,6 +28,8 @@ pub struct Rock {
 
     pos: Vector2,
     size: Vector2,
+    /// Movement per reference frame, once gravity wells start pulling it
+    drift: Vector2,
 
     lifespan: f32,
     step: i8,
@@ -35,13 +37,14 @@ pub struct Rock {
 }
 
 impl Rock {
-    pub fn new(rng: &mut ThreadRng, id: u16, pos: Vector2) -> Self {
+    pub fn new(rng: &mut StdRng, id: u16, pos: Vector2) -> Self {
         let tmp = rng.random_range(MIN_OBSTACLE_SIZE..=MAX_OBSTACLE_SIZE);
         let size = vec2(tmp, tmp);
         let pos = pos - size;
 
         Rock {
             pos, size, id,
+            drift: Vector2::zero(),
             lifespan: rng.random_range(MIN_OBSTACLE_LIFE..=MAX_OBSTACLE_LIFE),
             step: OBSTACLE_DELTA_ALPHA,
             alpha: OBSTACLE_START_ALPHA
@@ -58,6 +61,20 @@ impl Obstacle for Rock {
         self.size
     }
 
+    fn velocity(&self) -> Vector2 {
+        self.drift
+    }
+
+    fn pull(&mut self, fields: &[Field]) {
+        let center = self.pos + self.size / 2.0;
+
+        if fields.iter().any(|field| field.center.distance_to(center) < GRAVITY_WELL_CORE) {
+            self.kill();
+        } else {
+            self.drift += total_force(fields, center) * GRAVITY_WELL_ROCK_PULL;
+        }
+    }
+
     fn kill(&mut self) {
         self.alpha = 0;
     }
@@ -66,14 +83,17 @@ impl Obstacle for Rock {
         self.alpha > 0
     }
 
-    fn update(&mut self, frame_info: FrameInfo, _rng: &mut ThreadRng, draw: &mut impl RaylibDraw) {
+    fn update(&mut self, frame_info: FrameInfo, _rng: &mut StdRng) {
         if self.lifespan <= 0.0 {
             self.step = (-(OBSTACLE_DELTA_ALPHA as f32) * frame_info.delta_time) as i8;
         }
 
+        self.pos += self.drift * frame_info.delta_time;
         self.lifespan -= frame_info.delta_time;
         self.alpha = self.alpha.saturating_add_signed(self.step);
+    }
 
+    fn draw(&self, draw: &mut impl RaylibDraw) {
         draw.draw_rectangle(
             self.pos.x as i32, self.pos.y as i32, 
             self.size.x as i32, self.size.y as i32, 
@@ -86,6 +106,6 @@ impl Obstacle for Rock {
     }
 
     fn collides_other(&mut self, other: &AnyObstacle) -> bool {
-        square_collides(self.pos, self.size, other.pos(), other.size())
+        rect_collides_other(self.pos, self.size, other)
     }
 }
\ No newline at end of file
@@ -17,10 +17,10 @@ You should have received a copy of the GNU Affero General Public License
 along with !pong.  If not, see <http://www.gnu.org/licenses/>.
  */
 
-use rand::{rngs::ThreadRng, seq::IteratorRandom, Rng};
+use rand::{rngs::StdRng, seq::IteratorRandom, Rng};
 use raylib::{math::Vector2, prelude::RaylibDraw};
 
-use crate::{obstacle::{AnyObstacle, Obstacle}, utils::{square_collides, vec2}, FrameInfo, FG, INTERNAL_RESOLUTION, MAX_OBSTACLE_SIZE, MAX_ROCKET_SPEED, MIN_OBSTACLE_SIZE, MIN_ROCKET_SPEED, ROCKET_SHAKE};
+use crate::{obstacle::{rect_collides_other, AnyObstacle, Obstacle}, utils::{square_collides, vec2}, FrameInfo, FG, INTERNAL_RESOLUTION, MAX_OBSTACLE_SIZE, MAX_ROCKET_SPEED, MIN_OBSTACLE_SIZE, MIN_ROCKET_SPEED, ROCKET_SHAKE};
 
 #[derive(Debug)]
 pub enum Base {
@@ -28,7 +28,7 @@ pub enum Base {
 }
 
 impl Base {
-    pub fn random(rng: &mut ThreadRng) -> Self {
+    pub fn random(rng: &mut StdRng) -> Self {
         [Base::Left, Base::Right, Base::Top, Base::Bottom].into_iter().choose(rng).unwrap()
     }
 }
@@ -46,7 +46,7 @@ pub struct Rocket {
 }
 
 impl Rocket {
-    pub fn new(rng: &mut ThreadRng, id: u16, pos: Vector2, base: Base) -> Self {
+    pub fn new(rng: &mut StdRng, id: u16, pos: Vector2, base: Base) -> Self {
         let tmp = rng.random_range(MIN_OBSTACLE_SIZE..=MAX_OBSTACLE_SIZE);
 
         Self {
@@ -67,6 +67,15 @@ impl Obstacle for Rocket {
         self.size
     }
 
+    fn velocity(&self) -> Vector2 {
+        match self.base {
+            Base::Left => vec2(self.velocity, 0.0),
+            Base::Right => vec2(-self.velocity, 0.0),
+            Base::Top => vec2(0.0, self.velocity),
+            Base::Bottom => vec2(0.0, -self.velocity),
+        }
+    }
+
     fn kill(&mut self) {
         self.dead = true;
     }
@@ -84,11 +93,16 @@ impl Obstacle for Rocket {
         }
     }
 
-    fn update(&mut self, frame_info: FrameInfo, rng: &mut ThreadRng, draw: &mut impl RaylibDraw) {
+    fn update(&mut self, frame_info: FrameInfo, _rng: &mut StdRng) {
+        self.pos += self.velocity() * frame_info.delta_time;
+    }
+
+    fn draw(&self, draw: &mut impl RaylibDraw) {
+        // the shake is purely cosmetic, so it doesn't use the game's generator
+        let mut rng = rand::rng();
+
         match self.base {
             Base::Left => {
-                self.pos.x += self.velocity * frame_info.delta_time;
-
                 let mut draw_pos = self.pos;
                 draw_pos.y += rng.random_range(-ROCKET_SHAKE..=ROCKET_SHAKE);
                 
@@ -106,8 +120,6 @@ impl Obstacle for Rocket {
                 );
             }
             Base::Right => {
-                self.pos.x -= self.velocity * frame_info.delta_time;
-
                 let mut draw_pos = self.pos;
                 draw_pos.y += rng.random_range(-ROCKET_SHAKE..=ROCKET_SHAKE);
 
@@ -125,8 +137,6 @@ impl Obstacle for Rocket {
                 );
             }
             Base::Top => {
-                self.pos.y += self.velocity * frame_info.delta_time;
-
                 let mut draw_pos = self.pos;
                 draw_pos.x += rng.random_range(-ROCKET_SHAKE..=ROCKET_SHAKE);
 
@@ -144,8 +154,6 @@ impl Obstacle for Rocket {
                 );
             }
             Base::Bottom => {
-                self.pos.y -= self.velocity * frame_info.delta_time;
-
                 let mut draw_pos = self.pos;
                 draw_pos.x += rng.random_range(-ROCKET_SHAKE..=ROCKET_SHAKE);
 
@@ -170,6 +178,6 @@ impl Obstacle for Rocket {
     }
 
     fn collides_other(&mut self, other: &AnyObstacle) -> bool {
-        square_collides(self.pos, self.size, other.pos(), other.size())
+        rect_collides_other(self.pos, self.size, other)
     }
 }
\ No newline at end of file
@@ -19,10 +19,10 @@ along with !pong.  If not, see <http://www.gnu.org/licenses/>.
 
 use std::collections::HashMap;
 
-use rand::{rngs::ThreadRng, Rng};
+use rand::{rngs::StdRng, Rng};
 use raylib::math::Vector2;
 
-use crate::{INTERNAL_RESOLUTION, NO_OBSTACLES_CENTER_ZONE, OBSTACLE_GRID_DIV_X, OBSTACLE_GRID_DIV_Y, OBSTACLE_POS_VARIANCE, OBSTACLE_SAFE_ZONE};
+use crate::{utils::rects_overlap, INTERNAL_RESOLUTION, MAX_OBSTACLE_SIZE, NO_OBSTACLES_CENTER_ZONE, OBSTACLE_GRID_DIV_X, OBSTACLE_GRID_DIV_Y, OBSTACLE_POS_VARIANCE, OBSTACLE_SAFE_ZONE, PICKUP_MIN_PLAYER_DISTANCE, PICKUP_OBSTACLE_MARGIN, PICKUP_REACH_DISTANCE, PLAYER_SIZE, SPAWN_SAFE_RADIUS};
 
 #[derive(Debug)]
 pub struct ObstacleGrid {
@@ -81,36 +81,156 @@ impl ObstacleGrid {
         self.curr_id = 0;
     }
 
-    pub fn alloc(&mut self, mut player_pos: Vector2, rng: &mut ThreadRng) -> Option<(u16, Vector2)> {
-        if self.free.is_empty() {
-            return None;
+    /// Distance between neighbouring cells
+    fn cell_step() -> Vector2 {
+        Vector2 {
+            x: (INTERNAL_RESOLUTION.x as usize / OBSTACLE_GRID_DIV_X as usize) as f32,
+            y: (INTERNAL_RESOLUTION.y as usize / OBSTACLE_GRID_DIV_Y as usize) as f32
         }
+    }
 
-        player_pos.x = (player_pos.x / OBSTACLE_GRID_DIV_X).floor() * OBSTACLE_GRID_DIV_X;
-        player_pos.y = (player_pos.y / OBSTACLE_GRID_DIV_Y).floor() * OBSTACLE_GRID_DIV_Y;
+    /// Grid cell the player's center is in. It's only an actual cell if the player is within the grid, outside of the center zone
+    fn player_cell(player_pos: Vector2) -> Vector2 {
+        let origin = Vector2 { x: OBSTACLE_SAFE_ZONE.x.trunc(), y: OBSTACLE_SAFE_ZONE.y.trunc() };
+        let step = Self::cell_step();
+        let center = player_pos + Vector2::one() * (PLAYER_SIZE / 2.0);
 
-        let tmp_pos = self.free.swap_remove(rng.random_range(0..self.free.len()));
-        let mut pos = {
-            // don't spawn obstacles in the same cell as the player's
-            if tmp_pos == player_pos {
-                let (_, pos) = self.alloc(player_pos, rng)?;
-                self.free.push(tmp_pos);
-                pos
-            } else {
-                tmp_pos
-            }
-        };
+        Vector2 {
+            x: origin.x + ((center.x - origin.x) / step.x).floor() * step.x,
+            y: origin.y + ((center.y - origin.y) / step.y).floor() * step.y
+        }
+    }
 
+    fn activate(&mut self, cell: Vector2) -> u16 {
         let id = self.curr_id;
         self.curr_id = self.curr_id.wrapping_add(1);
-        self.active.insert(id, pos);
+        self.active.insert(id, cell);
+        id
+    }
+
+    /// Top left and bottom right corners of the area obstacles spawned in `cell` can cover.
+    /// They sit up and to the left of their position
+    fn cell_area(cell: Vector2) -> (Vector2, Vector2) {
+        (cell - Vector2::one() * (OBSTACLE_POS_VARIANCE + MAX_OBSTACLE_SIZE), cell + Vector2::one() * OBSTACLE_POS_VARIANCE)
+    }
 
+    /// Area obstacles spawned in the cell allocated with `id` can cover, as position and size
+    pub fn area(&self, id: u16) -> Option<(Vector2, Vector2)> {
+        let (min, max) = Self::cell_area(*self.active.get(&id)?);
+        Some((min, max - min))
+    }
+
+    /// Whether `cell` is the one the player is in, or an obstacle spawned in it could end up within `SPAWN_SAFE_RADIUS` of any point of the player's `path`
+    fn is_unsafe(cell: Vector2, path: &[Vector2]) -> bool {
+        if path.first().is_some_and(|pos| Self::player_cell(*pos) == cell) {
+            return true;
+        }
+
+        let (min, max) = Self::cell_area(cell);
+        let min = min - Vector2::one() * SPAWN_SAFE_RADIUS;
+        let max = max + Vector2::one() * SPAWN_SAFE_RADIUS;
+
+        path.iter().any(|pos| {
+            pos.x < max.x && min.x < pos.x + PLAYER_SIZE &&
+            pos.y < max.y && min.y < pos.y + PLAYER_SIZE
+        })
+    }
+
+    /// Picks a random free cell, avoiding the ones along the player's predicted `path`
+    pub fn alloc(&mut self, path: &[Vector2], rng: &mut StdRng) -> Option<(u16, Vector2)> {
+        let candidates: Vec<usize> = (0 .. self.free.len())
+            .filter(|i| !Self::is_unsafe(self.free[*i], path))
+            .collect();
+
+        if candidates.is_empty() {
+            return None;
+        }
+
+        let idx = candidates[rng.random_range(0..candidates.len())];
+        let cell = self.free.swap_remove(idx);
+        let id = self.activate(cell);
+
+        let mut pos = cell;
         pos.x += rng.random_range(-OBSTACLE_POS_VARIANCE..=OBSTACLE_POS_VARIANCE);
         pos.y += rng.random_range(-OBSTACLE_POS_VARIANCE..=OBSTACLE_POS_VARIANCE);
         
         Some((id, pos))
     }
 
+    /// Picks a random free cell next to another free one, horizontally or vertically, both away from the player's predicted `path`.
+    /// Only the first cell is allocated: it's returned along with its neighbour, which can be taken later with `claim`
+    pub fn alloc_pair(&mut self, path: &[Vector2], rng: &mut StdRng) -> Option<(u16, Vector2, Vector2)> {
+        let step = Self::cell_step();
+
+        let candidates: Vec<(usize, Vector2)> = (0 .. self.free.len())
+            .filter(|i| !Self::is_unsafe(self.free[*i], path))
+            .flat_map(|i| [(i, self.free[i] + Vector2 { x: step.x, y: 0.0 }), (i, self.free[i] + Vector2 { x: 0.0, y: step.y })])
+            .filter(|(_, neighbour)| self.free.contains(neighbour) && !Self::is_unsafe(*neighbour, path))
+            .collect();
+
+        if candidates.is_empty() {
+            return None;
+        }
+
+        let (idx, neighbour) = candidates[rng.random_range(0..candidates.len())];
+        let (start, end) = if rng.random_bool(0.5) { (self.free[idx], neighbour) } else { (neighbour, self.free[idx]) };
+
+        let id = self.claim(start).expect("Cell is free");
+        Some((id, start, end))
+    }
+
+    /// Allocates the given cell, if it's free
+    pub fn claim(&mut self, cell: Vector2) -> Option<u16> {
+        let idx = self.free.iter().position(|x| *x == cell)?;
+        self.free.swap_remove(idx);
+        Some(self.activate(cell))
+    }
+
+    /// Whether a pickup of the given size fits in `cell`, inside the safe zone and far enough from the player and from `obstacles`
+    fn fits_pickup(cell: Vector2, size: f32, player_pos: Vector2, obstacles: &[(Vector2, Vector2)]) -> bool {
+        if cell.x > INTERNAL_RESOLUTION.x - OBSTACLE_SAFE_ZONE.x || cell.y > INTERNAL_RESOLUTION.y - OBSTACLE_SAFE_ZONE.y {
+            return false;
+        }
+
+        let center = cell + Vector2::one() * (size / 2.0);
+        if center.distance_to(player_pos + Vector2::one() * (PLAYER_SIZE / 2.0)) < PICKUP_MIN_PLAYER_DISTANCE {
+            return false;
+        }
+
+        let area_pos = cell - Vector2::one() * PICKUP_OBSTACLE_MARGIN;
+        let area_size = Vector2::one() * (size + PICKUP_OBSTACLE_MARGIN * 2.0);
+        !obstacles.iter().any(|(pos, obstacle_size)| rects_overlap(area_pos, area_size, *pos, *obstacle_size))
+    }
+
+    /// Whether the player's predicted `path` passes close to a pickup of the given size placed in `cell`
+    fn is_reachable(cell: Vector2, size: f32, path: &[Vector2]) -> bool {
+        let center = cell + Vector2::one() * (size / 2.0);
+        path.iter().any(|pos| (*pos + Vector2::one() * (PLAYER_SIZE / 2.0)).distance_to(center) <= PICKUP_REACH_DISTANCE)
+    }
+
+    /// Picks a free cell to place a pickup of the given size in, preferring the ones the player's predicted `path` passes near.
+    /// `obstacles` are given as position and size. The cell stays free, as pickups aren't obstacles
+    pub fn pickup_spot(&self, path: &[Vector2], obstacles: &[(Vector2, Vector2)], size: f32, rng: &mut StdRng) -> Option<Vector2> {
+        let player_pos = *path.first()?;
+
+        let candidates: Vec<Vector2> = self.free.iter()
+            .copied()
+            .filter(|cell| Self::fits_pickup(*cell, size, player_pos, obstacles))
+            .collect();
+
+        let reachable: Vec<Vector2> = candidates.iter()
+            .copied()
+            .filter(|cell| Self::is_reachable(*cell, size, path))
+            .collect();
+
+        let choices = if reachable.is_empty() { &candidates } else { &reachable };
+        if choices.is_empty() {
+            None
+        } else {
+            Some(choices[rng.random_range(0..choices.len())])
+        }
+    }
+
     pub fn free(&mut self, id: u16) -> Result<(), ()> {
         if let Some(pos) = self.active.remove(&id) {
             self.free.push(pos);
@@ -119,4 +239,338 @@ impl ObstacleGrid {
             Err(())
         }
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use rand::SeedableRng;
+
+    use crate::{obstacle::{rock::Rock, Obstacle}, BARRIER_LENGTH, BARRIER_WIDTH, LASER_WIDTH, MIN_OBSTACLE_SIZE};
+
+    use super::*;
+
+    const SEEDS: u64 = 200;
+    const OPS: usize = 500;
+    const PATH_LEN: usize = 20;
+
+    fn key(pos: Vector2) -> (i32, i32) {
+        (pos.x as i32, pos.y as i32)
+    }
+
+    fn random_pos(rng: &mut StdRng) -> Vector2 {
+        Vector2 {
+            x: rng.random_range(0.0..INTERNAL_RESOLUTION.x),
+            y: rng.random_range(0.0..INTERNAL_RESOLUTION.y)
+        }
+    }
+
+    /// A straight path from a random position, sometimes starting right on a cell so the exclusion actually kicks in
+    fn random_path(grid: &ObstacleGrid, rng: &mut StdRng) -> Vec<Vector2> {
+        let start = {
+            if rng.random_bool(0.5) && !grid.free.is_empty() {
+                grid.free[rng.random_range(0..grid.free.len())]
+            } else {
+                random_pos(rng)
+            }
+        };
+
+        let step = Vector2 { x: rng.random_range(-4.0..4.0), y: rng.random_range(-3.0..3.0) };
+        (0 .. PATH_LEN).map(|i| start + step * i as f32).collect()
+    }
+
+    fn check_invariants(grid: &ObstacleGrid, all_cells: &HashSet<(i32, i32)>) {
+        assert_eq!(grid.free.len() + grid.active.len(), all_cells.len(), "cells were lost or duplicated");
+
+        let mut seen = HashSet::new();
+        for cell in grid.free.iter().chain(grid.active.values()) {
+            assert!(all_cells.contains(&key(*cell)), "{:?} is not a grid cell", cell);
+            assert!(seen.insert(key(*cell)), "{:?} is tracked twice", cell);
+        }
+    }
+
+    #[test]
+    fn random_operations_keep_cells_consistent() {
+        let all_cells: HashSet<(i32, i32)> = ObstacleGrid::init_free().into_iter().map(key).collect();
+
+        for seed in 0 .. SEEDS {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let mut grid = ObstacleGrid::new();
+            let mut ids: Vec<u16> = Vec::new();
+
+            for _ in 0 .. OPS {
+                match rng.random_range(0..10) {
+                    0 ..= 5 => {
+                        let path = random_path(&grid, &mut rng);
+                        let free_before = grid.free.len();
+                        let safe_before = grid.free.iter().filter(|cell| !ObstacleGrid::is_unsafe(**cell, &path)).count();
+
+                        match grid.alloc(&path, &mut rng) {
+                            Some((id, pos)) => {
+                                let cell = grid.active[&id];
+                                assert!(!ObstacleGrid::is_unsafe(cell, &path), "allocated a cell along the player's path");
+                                assert_ne!(cell, ObstacleGrid::player_cell(path[0]), "allocated the player's cell");
+                                assert!((pos.x - cell.x).abs() <= OBSTACLE_POS_VARIANCE && (pos.y - cell.y).abs() <= OBSTACLE_POS_VARIANCE);
+                                assert_eq!(grid.free.len(), free_before - 1);
+                                ids.push(id);
+                            }
+                            None => {
+                                assert_eq!(grid.free.len(), free_before);
+                                assert_eq!(safe_before, 0);
+                            }
+                        }
+                    }
+                    6 ..= 8 if !ids.is_empty() => {
+                        let id = ids.swap_remove(rng.random_range(0..ids.len()));
+                        assert_eq!(grid.free(id), Ok(()));
+                        assert_eq!(grid.free(id), Err(()), "double free must fail");
+                    }
+                    _ => {
+                        grid.reset();
+                        ids.clear();
+                        assert!(grid.active.is_empty());
+                    }
+                }
+
+                check_invariants(&grid, &all_cells);
+            }
+        }
+    }
+
+    #[test]
+    fn exhausting_the_grid_spares_the_players_path() {
+        for seed in 0 .. SEEDS {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let mut grid = ObstacleGrid::new();
+
+            let path = random_path(&grid, &mut rng);
+            let safe = grid.free.iter().filter(|cell| !ObstacleGrid::is_unsafe(**cell, &path)).count();
+            let unsafe_cells = grid.free.len() - safe;
+
+            let mut allocated = 0;
+            while grid.alloc(&path, &mut rng).is_some() {
+                allocated += 1;
+                assert!(allocated <= safe);
+            }
+
+            assert_eq!(allocated, safe);
+            assert_eq!(grid.free.len(), unsafe_cells);
+            assert!(grid.free.iter().all(|cell| ObstacleGrid::is_unsafe(*cell, &path)));
+        }
+    }
+
+    #[test]
+    fn exhausting_the_grid_spares_the_players_cell() {
+        let step = ObstacleGrid::cell_step();
+
+        for seed in 0 .. SEEDS {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let mut grid = ObstacleGrid::new();
+
+            // anywhere inside a random cell
+            let cell = grid.free[rng.random_range(0..grid.free.len())];
+            let offset = Vector2 { x: rng.random_range(0.0..step.x), y: rng.random_range(0.0..step.y) };
+            let player_pos = cell + offset - Vector2::one() * (PLAYER_SIZE / 2.0);
+
+            let player_cell = ObstacleGrid::player_cell(player_pos);
+            assert_eq!(player_cell, cell, "the player's cell is not on the grid");
+
+            while let Some((id, _)) = grid.alloc(&[player_pos], &mut rng) {
+                assert_ne!(grid.active[&id], player_cell, "allocated the player's cell");
+            }
+
+            assert!(grid.free.contains(&player_cell));
+        }
+    }
+
+    #[test]
+    fn only_unsafe_cells_left() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut grid = ObstacleGrid::new();
+
+        for _ in 0 .. 1000 {
+            let player_pos = random_pos(&mut rng);
+            let cells: Vec<Vector2> = ObstacleGrid::init_free().into_iter()
+                .filter(|cell| ObstacleGrid::is_unsafe(*cell, &[player_pos]))
+                .collect();
+
+            let len = cells.len();
+            grid.free = cells;
+            assert_eq!(grid.alloc(&[player_pos], &mut rng), None);
+            assert_eq!(grid.free.len(), len);
+        }
+    }
+
+    #[test]
+    fn the_players_cell_is_unsafe() {
+        for cell in ObstacleGrid::init_free() {
+            assert!(ObstacleGrid::is_unsafe(cell, &[cell]));
+            assert!(ObstacleGrid::is_unsafe(cell, &[cell - Vector2::one() * MAX_OBSTACLE_SIZE]));
+            assert!(!ObstacleGrid::is_unsafe(cell, &[cell - Vector2::one() * (OBSTACLE_POS_VARIANCE + MAX_OBSTACLE_SIZE + SPAWN_SAFE_RADIUS + PLAYER_SIZE)]));
+        }
+    }
+
+    #[test]
+    fn rocks_stay_clear_of_the_players_path() {
+        let reach = Vector2::one() * SPAWN_SAFE_RADIUS;
+
+        for seed in 0 .. SEEDS {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let mut grid = ObstacleGrid::new();
+
+            // paths starting on a cell are moved up and to the left of it, where its rocks end up
+            let shift = Vector2::one() * rng.random_range(0.0 .. OBSTACLE_POS_VARIANCE + MAX_OBSTACLE_SIZE + SPAWN_SAFE_RADIUS);
+            let path: Vec<Vector2> = random_path(&grid, &mut rng).into_iter().map(|pos| pos - shift).collect();
+
+            while let Some((id, pos)) = grid.alloc(&path, &mut rng) {
+                let rock = Rock::new(&mut rng, id, pos);
+
+                for pos in &path {
+                    assert!(
+                        !rects_overlap(rock.pos() - reach, rock.size() + reach * 2.0, *pos, Vector2::one() * PLAYER_SIZE),
+                        "rock at {:?} spawned too close to the player at {:?}", rock.pos(), pos
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn pickups_keep_their_distance() {
+        const SIZE: f32 = 8.0;
+
+        let mut placed = 0;
+        let mut reachable = 0;
+
+        for seed in 0 .. SEEDS * 5 {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let mut grid = ObstacleGrid::new();
+
+            let mut obstacles = Vec::new();
+            for _ in 0 .. rng.random_range(0..40) {
+                if let Some((_, pos)) = grid.alloc(&[], &mut rng) {
+                    obstacles.push((pos, Vector2::one() * rng.random_range(MIN_OBSTACLE_SIZE..=MAX_OBSTACLE_SIZE)));
+                }
+            }
+
+            // long and thin ones, like barriers and laser beams, whose corners can lie outside of a pickup's area
+            if rng.random_bool(0.5) {
+                let pos = random_pos(&mut rng);
+                obstacles.push((pos, Vector2 { x: BARRIER_LENGTH, y: BARRIER_WIDTH }));
+                obstacles.push((pos, Vector2 { x: BARRIER_WIDTH, y: BARRIER_LENGTH }));
+                obstacles.push((Vector2 { x: 0.0, y: pos.y }, Vector2 { x: INTERNAL_RESOLUTION.x, y: LASER_WIDTH }));
+            }
+
+            let path = random_path(&grid, &mut rng);
+            let player_pos = path[0];
+
+            match grid.pickup_spot(&path, &obstacles, SIZE, &mut rng) {
+                Some(pos) => {
+                    placed += 1;
+
+                    assert!(grid.free.contains(&pos), "pickups must be placed on free cells");
+                    assert!(ObstacleGrid::fits_pickup(pos, SIZE, player_pos, &obstacles));
+                    assert!((pos + Vector2::one() * (SIZE / 2.0)).distance_to(player_pos + Vector2::one() * (PLAYER_SIZE / 2.0)) >= PICKUP_MIN_PLAYER_DISTANCE);
+
+                    for (obstacle_pos, obstacle_size) in &obstacles {
+                        assert!(!rects_overlap(pos, Vector2::one() * SIZE, *obstacle_pos, *obstacle_size), "pickup placed on an obstacle");
+                    }
+
+                    let any_reachable = grid.free.iter()
+                        .any(|cell| ObstacleGrid::fits_pickup(*cell, SIZE, player_pos, &obstacles) && ObstacleGrid::is_reachable(*cell, SIZE, &path));
+
+                    if any_reachable {
+                        reachable += 1;
+                        assert!(ObstacleGrid::is_reachable(pos, SIZE, &path), "a reachable spot was available");
+                    }
+                }
+                None => {
+                    assert!(grid.free.iter().all(|cell| !ObstacleGrid::fits_pickup(*cell, SIZE, player_pos, &obstacles)));
+                }
+            }
+        }
+
+        // make sure the properties above were actually exercised
+        assert!(placed > 0 && reachable > 0);
+    }
+
+    #[test]
+    fn pickup_spot_needs_a_path() {
+        let mut rng = StdRng::seed_from_u64(0);
+        assert_eq!(ObstacleGrid::new().pickup_spot(&[], &[], 8.0, &mut rng), None);
+    }
+
+    #[test]
+    fn pairs_are_neighbours() {
+        let all_cells: HashSet<(i32, i32)> = ObstacleGrid::init_free().into_iter().map(key).collect();
+        let step = ObstacleGrid::cell_step();
+
+        for seed in 0 .. SEEDS {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let mut grid = ObstacleGrid::new();
+            let mut ids = Vec::new();
+
+            loop {
+                let path = random_path(&grid, &mut rng);
+                let Some((id, start, end)) = grid.alloc_pair(&path, &mut rng) else {
+                    break;
+                };
+
+                let offset = end - start;
+                assert!(
+                    (offset.x.abs() == step.x && offset.y == 0.0) || (offset.x == 0.0 && offset.y.abs() == step.y),
+                    "{:?} and {:?} are not neighbours", start, end
+                );
+
+                assert_eq!(grid.active[&id], start);
+                assert!(!ObstacleGrid::is_unsafe(start, &path) && !ObstacleGrid::is_unsafe(end, &path), "allocated a cell along the player's path");
+                assert!(grid.free.contains(&end), "the other end must stay free");
+                ids.push(id);
+
+                if rng.random_bool(0.5) {
+                    ids.push(grid.claim(end).expect("the other end must be claimable"));
+                    assert_eq!(grid.claim(end), None, "claimed the same cell twice");
+                }
+
+                check_invariants(&grid, &all_cells);
+            }
+
+            for id in ids {
+                assert_eq!(grid.free(id), Ok(()));
+            }
+
+            assert!(grid.active.is_empty());
+            check_invariants(&grid, &all_cells);
+        }
+    }
+
+    #[test]
+    fn claiming_busy_cells_fails() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut grid = ObstacleGrid::new();
+
+        assert_eq!(grid.claim(Vector2 { x: -1.0, y: -1.0 }), None);
+
+        let (id, _) = grid.alloc(&[], &mut rng).unwrap();
+        let cell = grid.active[&id];
+        assert_eq!(grid.claim(cell), None);
+
+        assert_eq!(grid.free(id), Ok(()));
+        let id = grid.claim(cell).unwrap();
+        assert_eq!(grid.active[&id], cell);
+    }
+
+    #[test]
+    fn freeing_unknown_ids_fails() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut grid = ObstacleGrid::new();
+
+        assert_eq!(grid.free(0), Err(()));
+
+        let (id, _) = grid.alloc(&[], &mut rng).unwrap();
+        assert_eq!(grid.free(id.wrapping_add(1)), Err(()));
+        assert_eq!(grid.free(id), Ok(()));
+        assert_eq!(grid.free(id), Err(()));
+    }
 }
\ No newline at end of file
@@ -17,15 +17,17 @@ You should have received a copy of the GNU Affero General Public License
 along with !pong.  If not, see <http://www.gnu.org/licenses/>.
  */
 
-use rand::{rngs::ThreadRng, Rng};
+use rand::{rngs::StdRng, Rng};
 use raylib::{math::Vector2, prelude::RaylibDraw};
 
-use crate::{EFFECTIVE_PAD_FRMT, FG, INTERNAL_RESOLUTION, OBSTACLE_SAFE_ZONE, PAD_SIZE, PAD_WALL_DISTANCE, PLAYER_SIZE};
+use crate::{EFFECTIVE_PAD_FRMT, FG, INTERNAL_RESOLUTION, OBSTACLE_SAFE_ZONE, PAD_RESIZE_VELOCITY, PAD_SIZE, PAD_WALL_DISTANCE, PLAYER_SIZE};
 
 #[derive(Debug)]
 pub struct Pad {
     pub pos: Vector2,
     size: Vector2,
+    /// Height the pad is growing or shrinking to
+    target_height: f32,
 
     is_left: bool,
 
@@ -49,39 +51,52 @@ impl Pad {
             }
         };
 
-        Self {
-            pos,
-            is_left,
-            size: PAD_SIZE,
-            cnt: 0.0,
-            step: 0.0,
-        }
+        Self::new(is_left, pos, PAD_SIZE)
     }
 
-    #[allow(unused)]
     pub fn new(is_left: bool, pos: Vector2, size: Vector2) -> Self {
         Self {
             pos,
             is_left,
             size,
+            target_height: size.y,
             cnt: 0.0,
             step: 0.0,
         }
     }
 
+    pub fn size(&self) -> Vector2 {
+        self.size
+    }
+
+    /// Vertical position the pad will stop at, once it's done moving
+    pub fn destination(&self) -> f32 {
+        self.pos.y + self.step * (EFFECTIVE_PAD_FRMT - self.cnt).max(0.0) + (self.size.y - self.target_height) / 2.0
+    }
+
     fn move_to(&mut self, pos: f32) {
-        if self.size == PAD_SIZE {
-            self.cnt = 0.0;
-            self.step = (pos - self.pos.y) / EFFECTIVE_PAD_FRMT;
-        }
+        self.cnt = 0.0;
+        self.step = (pos - self.pos.y) / EFFECTIVE_PAD_FRMT;
+    }
+
+    /// Grows or shrinks the pad to `height` over a few frames, around its center
+    pub fn resize(&mut self, height: f32) {
+        self.target_height = height;
+    }
+
+    /// Lowest position the top of the pad can be moved to, fitting both its current and target height
+    fn max_y(&self) -> f32 {
+        INTERNAL_RESOLUTION.y - OBSTACLE_SAFE_ZONE.y - self.size.y.max(self.target_height)
     }
 
     pub fn reset(&mut self) {
+        self.size = PAD_SIZE;
+        self.target_height = PAD_SIZE.y;
         self.pos.y = INTERNAL_RESOLUTION.y / 2.0 - self.size.y / 2.0;
         self.step = 0.0;
     }
 
-    pub fn update(&mut self, delta_time: f32, draw: &mut impl RaylibDraw) {
+    pub fn update(&mut self, delta_time: f32) {
         self.pos.y += self.step * delta_time;
 
         self.cnt += delta_time;
@@ -89,6 +104,16 @@ impl Pad {
             self.step = 0.0;
         }
 
+        if self.size.y != self.target_height {
+            let max_change = PAD_RESIZE_VELOCITY * delta_time;
+            let change = (self.target_height - self.size.y).clamp(-max_change, max_change);
+
+            self.size.y += change;
+            self.pos.y = (self.pos.y - change / 2.0).clamp(OBSTACLE_SAFE_ZONE.y, self.max_y());
+        }
+    }
+
+    pub fn draw(&self, draw: &mut impl RaylibDraw) {
         draw.draw_rectangle(
             self.pos.x as i32, self.pos.y as i32, 
             self.size.x as i32, self.size.y as i32, 
@@ -120,9 +145,9 @@ impl Pad {
         false
     }
 
-    pub fn move_if_collides(&mut self, player_pos: Vector2, tolerance: f32, rng: &mut ThreadRng) -> bool {
+    pub fn move_if_collides(&mut self, player_pos: Vector2, tolerance: f32, rng: &mut StdRng) -> bool {
         if self.collides(player_pos, tolerance) {
-            self.move_to(rng.random_range(OBSTACLE_SAFE_ZONE.y .. INTERNAL_RESOLUTION.y - OBSTACLE_SAFE_ZONE.y - self.size.y));
+            self.move_to(rng.random_range(OBSTACLE_SAFE_ZONE.y .. self.max_y()));
             true
         } else {
             false
@@ -17,9 +17,10 @@ You should have received a copy of the GNU Affero General Public License
 along with !pong.  If not, see <http://www.gnu.org/licenses/>.
  */
 
+use rand::{seq::IteratorRandom, Rng};
 use raylib::{color::Color, math::{Rectangle, Vector2}, prelude::RaylibDraw};
 
-use crate::{utils::{square_collides, vec2}, BOMB_ANGLE_INCREMENT, BOMB_LIFE, BOMB_SIZE, RAINBOW_DELTA};
+use crate::{explosion::Explosion, obstacle::{explosion::ObstacleExplosion, AnyObstacle, Obstacle}, pickup::{home, Pickup, PickupContext}, utils::vec2, world::WorldEvent, FrameInfo, BOMB_ANGLE_INCREMENT, BOMB_LIFE, BOMB_MAX_DESTROYED_OBSTACLES, BOMB_MIN_DESTROYED_OBSTACLES, BOMB_SIZE, INTERNAL_RESOLUTION, OBSTACLE_COLLISION_MAX_VELOCITY, RAINBOW_DELTA, SCORE_HITBOX_SIZE};
 
 #[derive(Debug)]
 pub struct Bomb {
@@ -31,14 +32,12 @@ pub struct Bomb {
 
     color_cnt: f32,
 
-    pub to_destroy: Vec<Rectangle>,
-    pub give_points: u64,
+    to_destroy: Vec<Rectangle>,
+    give_points: u64,
 }
 
 impl Bomb {
     pub fn new(pos: Vector2) -> Self {
-        let pos = pos - BOMB_SIZE / 2.0;
-
         Self {
             pos,
             velocity: Vector2::zero(),
@@ -49,16 +48,78 @@ impl Bomb {
             give_points: 0
         }
     }
+}
+
+impl Pickup for Bomb {
+    fn name(&self) -> &'static str {
+        "bomb"
+    }
 
-    pub fn collides(&self, pos: Vector2, size: Vector2) -> bool {
-        square_collides(self.pos, vec2(BOMB_SIZE, BOMB_SIZE), pos, size)
+    fn pos(&self) -> Vector2 {
+        self.pos
     }
 
-    pub fn is_alive(&self) -> bool {
+    fn size(&self) -> Vector2 {
+        vec2(BOMB_SIZE, BOMB_SIZE)
+    }
+
+    fn is_alive(&self) -> bool {
         self.lifetime > 0.0
     }
 
-    pub fn update(&mut self, delta_time: f32, draw: &mut impl RaylibDraw) {
+    fn can_collect(&self) -> bool {
+        self.to_destroy.is_empty()
+    }
+
+    fn destroys_obstacles(&self) -> bool {
+        true
+    }
+
+    fn collect(&mut self, ctx: &mut PickupContext) {
+        // select `amount` random rocks' or asteroids' positions to destroy them
+        let amount = ctx.rng.random_range(BOMB_MIN_DESTROYED_OBSTACLES..=BOMB_MAX_DESTROYED_OBSTACLES);
+        let mut to_destroy: Vec<Rectangle> = ctx.obstacles.iter()
+            .filter(|x| matches!(x, AnyObstacle::Rock(_) | AnyObstacle::Asteroid(_)))
+            .choose_multiple(ctx.rng, amount)
+            .into_iter()
+            .map(|obstacle| {
+                let pos = obstacle.pos();
+                let size = obstacle.size();
+                Rectangle { x: pos.x, y: pos.y, width: size.x, height: size.y }
+            })
+            .collect();
+
+        // if there are no obstacles to destroy, the bomb will give points instead
+        if to_destroy.is_empty() {
+            self.give_points = amount as u64;
+            self.to_destroy.push(Rectangle {
+                x: INTERNAL_RESOLUTION.x / 2.0 - SCORE_HITBOX_SIZE / 2.0,
+                y: INTERNAL_RESOLUTION.y / 2.0 - SCORE_HITBOX_SIZE / 2.0,
+                width:  SCORE_HITBOX_SIZE,
+                height: SCORE_HITBOX_SIZE
+            });
+        } else {
+            self.to_destroy.append(&mut to_destroy);
+        }
+    }
+
+    fn expire(&mut self, ctx: &mut PickupContext) {
+        if self.give_points > 0 {
+            ctx.events.push(WorldEvent::BombPoints(self.give_points));
+            ctx.player.count += self.give_points;
+            let mut explosion = Explosion::new(self.pos);
+            explosion.explode(OBSTACLE_COLLISION_MAX_VELOCITY, true, ctx.rng);
+            ctx.obstacles.push(ObstacleExplosion(explosion).into());
+        }
+    }
+
+    fn attract(&mut self, target: Vector2) {
+        home(&mut self.pos, &mut self.velocity, target);
+    }
+
+    fn update(&mut self, frame_info: FrameInfo, _ctx: &mut PickupContext) {
+        let delta_time = frame_info.delta_time;
+
         if self.to_destroy.len() > 0 {
             let to_destroy = *self.to_destroy.last().unwrap();
             let to_destroy_pos = Vector2 { x: to_destroy.x, y: to_destroy.y };
@@ -72,14 +133,20 @@ impl Bomb {
                     self.lifetime = 0.0;
                 }
             } else {
-                self.velocity -= (self.pos - to_destroy_pos) * 0.0025;
-                self.pos += self.velocity;
-                self.velocity *= 0.95;
+                home(&mut self.pos, &mut self.velocity, to_destroy_pos);
             }
         } else {
             self.lifetime -= delta_time;
         }
 
+        self.angle += BOMB_ANGLE_INCREMENT * delta_time;
+        self.color_cnt += RAINBOW_DELTA * delta_time;
+        if self.color_cnt > 1.0 {
+            self.color_cnt = 0.0;
+        }
+    }
+
+    fn draw(&self, draw: &mut impl RaylibDraw) {
         let color = Color::color_from_hsv(self.color_cnt * 360.0, 1.0, 1.0);
 
         const HALF_SIZE: f32 = BOMB_SIZE / 2.0;
@@ -113,11 +180,5 @@ impl Bomb {
             draw.draw_triangle(v0, vl, vr, color);
             draw.draw_triangle(v1, vr, vl, color);
         }
-        
-        self.angle += BOMB_ANGLE_INCREMENT * delta_time;
-        self.color_cnt += RAINBOW_DELTA * delta_time;
-        if self.color_cnt > 1.0 {
-            self.color_cnt = 0.0;
-        }
     }
 }
\ No newline at end of file
@@ -0,0 +1,72 @@
+/*
+Copyright (C) 2025 Amari Calipso
+
+This file is part of !pong.
+
+!pong is free software: you can redistribute it and/or modify
+it under the terms of the GNU Affero General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+!pong is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Affero General Public License for more details.
+
+You should have received a copy of the GNU Affero General Public License
+along with !pong.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+
+use raylib::{math::Vector2, prelude::RaylibDraw};
+
+use crate::{pickup::{PickupContext, Timed, TimedPickup}, FG, MAGNET_DURATION, MAGNET_LIFE, MAGNET_SIZE};
+
+/// Pulls nearby pickups toward the player for a while
+#[derive(Debug)]
+pub struct Magnet(Timed);
+
+impl Magnet {
+    pub fn new(pos: Vector2) -> Self {
+        Self(Timed::new(pos, MAGNET_SIZE, MAGNET_LIFE))
+    }
+}
+
+impl TimedPickup for Magnet {
+    fn name(&self) -> &'static str {
+        "magnet"
+    }
+
+    fn timed(&self) -> &Timed {
+        &self.0
+    }
+
+    fn timed_mut(&mut self) -> &mut Timed {
+        &mut self.0
+    }
+
+    fn apply(&mut self, ctx: &mut PickupContext) {
+        ctx.effects.magnet = MAGNET_DURATION;
+    }
+
+    fn draw_at(&self, pos: Vector2, draw: &mut impl RaylibDraw) {
+        // horseshoe
+        draw.draw_rectangle(
+            pos.x as i32, pos.y as i32, 
+            2, MAGNET_SIZE as i32, 
+            FG
+        );
+
+        draw.draw_rectangle(
+            (pos.x + MAGNET_SIZE - 2.0) as i32, pos.y as i32, 
+            2, MAGNET_SIZE as i32, 
+            FG
+        );
+
+        draw.draw_rectangle(
+            pos.x as i32, (pos.y + MAGNET_SIZE - 2.0) as i32, 
+            MAGNET_SIZE as i32, 2, 
+            FG
+        );
+    }
+}
\ No newline at end of file
@@ -0,0 +1,257 @@
+/*
+Copyright (C) 2025 Amari Calipso
+
+This file is part of !pong.
+
+!pong is free software: you can redistribute it and/or modify
+it under the terms of the GNU Affero General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+!pong is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Affero General Public License for more details.
+
+You should have received a copy of the GNU Affero General Public License
+along with !pong.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use enum_dispatch::enum_dispatch;
+use rand::rngs::StdRng;
+use raylib::{math::Vector2, prelude::RaylibDraw};
+
+use crate::{obstacle::AnyObstacle, pad::Pad, pickup::{bomb::Bomb, magnet::Magnet, rainbow::Rainbow, shield::Shield, slow_motion::SlowMotion, wide_pad::WidePad}, player::Player, utils::square_collides, world::WorldEvent, FrameInfo, BOMB_PROBABILITY, BOMB_SIZE, HOMING_DAMPING, HOMING_STIFFNESS, MAGNET_PROBABILITY, MAGNET_SIZE, PICKUP_BLINK_TIME, PLAYER_COUNT_BOMB, PLAYER_COUNT_MAGNET, PLAYER_COUNT_RAINBOW, PLAYER_COUNT_SHIELD, PLAYER_COUNT_SLOW_MOTION, PLAYER_COUNT_WIDE_PAD, RAINBOW_PROBABILITY, RAINBOW_SIZE, SHIELD_PROBABILITY, SHIELD_SIZE, SLOW_MOTION_PROBABILITY, SLOW_MOTION_SCALE, SLOW_MOTION_SIZE, WIDE_PAD_PROBABILITY, WIDE_PAD_SIZE};
+
+pub mod bomb;
+pub mod magnet;
+pub mod rainbow;
+pub mod shield;
+pub mod slow_motion;
+pub mod wide_pad;
+
+/// Timed effects granted by pickups, in frames left
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Effects {
+    pub slow_motion: f32,
+    pub magnet: f32
+}
+
+impl Effects {
+    pub fn update(&mut self, delta_time: f32) {
+        self.slow_motion = (self.slow_motion - delta_time).max(0.0);
+        self.magnet = (self.magnet - delta_time).max(0.0);
+    }
+
+    pub fn magnet(&self) -> bool {
+        self.magnet > 0.0
+    }
+
+    pub fn slow_motion(&self) -> bool {
+        self.slow_motion > 0.0
+    }
+
+    /// How fast time flows for obstacles
+    pub fn obstacle_time_scale(&self) -> f32 {
+        if self.slow_motion() { SLOW_MOTION_SCALE } else { 1.0 }
+    }
+}
+
+/// Whether a pickup that's about to disappear should be hidden in this moment of its `lifetime`
+pub fn is_blinking(lifetime: f32) -> bool {
+    lifetime < PICKUP_BLINK_TIME && (lifetime / (PICKUP_BLINK_TIME / 8.0)) as i32 % 2 == 0
+}
+
+/// Spring-like motion of `pos` toward `target`
+pub fn home(pos: &mut Vector2, velocity: &mut Vector2, target: Vector2) {
+    *velocity -= (*pos - target) * HOMING_STIFFNESS;
+    *pos += *velocity;
+    *velocity *= HOMING_DAMPING;
+}
+
+/// The parts of the world a pickup can act on
+pub struct PickupContext<'a> {
+    pub player: &'a mut Player,
+    pub left_pad: &'a mut Pad,
+    pub right_pad: &'a mut Pad,
+    /// Pad hits left before the pads go back to their normal size
+    pub custom_pad_count: &'a mut u32,
+    pub effects: &'a mut Effects,
+    pub obstacles: &'a mut Vec<AnyObstacle>,
+    pub events: &'a mut Vec<WorldEvent>,
+    pub rng: &'a mut StdRng
+}
+
+#[enum_dispatch(AnyPickup)]
+pub trait Pickup {
+    /// Identifies the kind of pickup in events and statistics
+    fn name(&self) -> &'static str;
+
+    fn pos(&self) -> Vector2;
+    fn size(&self) -> Vector2;
+
+    fn is_alive(&self) -> bool;
+
+    /// Whether the player can collect it right now
+    fn can_collect(&self) -> bool {
+        true
+    }
+
+    /// Whether obstacles touching it get destroyed
+    fn destroys_obstacles(&self) -> bool {
+        false
+    }
+
+    fn collides(&self, pos: Vector2, size: Vector2) -> bool {
+        square_collides(self.pos(), self.size(), pos, size)
+    }
+
+    /// Pulls the pickup's top left corner toward `target`, on every frame the magnet reaches it
+    fn attract(&mut self, _target: Vector2) {}
+
+    fn update(&mut self, frame_info: FrameInfo, ctx: &mut PickupContext);
+
+    /// Applies the effect of the pickup, once the player touches it
+    fn collect(&mut self, ctx: &mut PickupContext);
+
+    /// Called once, right before the pickup is removed for not being alive anymore
+    fn expire(&mut self, _ctx: &mut PickupContext) {}
+
+    fn draw(&self, draw: &mut impl RaylibDraw);
+}
+
+/// Position, drift and remaining lifetime of a pickup that waits to be collected and applies its effect at once
+#[derive(Debug)]
+pub struct Timed {
+    pos: Vector2,
+    velocity: Vector2,
+    lifetime: f32,
+    size: f32
+}
+
+impl Timed {
+    pub fn new(pos: Vector2, size: f32, lifetime: f32) -> Self {
+        Self {
+            pos, size, lifetime,
+            velocity: Vector2::zero()
+        }
+    }
+}
+
+/// Pickup built on `Timed`: only its effect and its looks differ from the others
+pub trait TimedPickup {
+    fn name(&self) -> &'static str;
+
+    fn timed(&self) -> &Timed;
+    fn timed_mut(&mut self) -> &mut Timed;
+
+    /// Applies the effect, once the player touches the pickup
+    fn apply(&mut self, ctx: &mut PickupContext);
+
+    /// Animates the pickup, on every frame it's alive
+    fn animate(&mut self, _frame_info: FrameInfo) {}
+
+    /// Draws the pickup with its top left corner at `pos`
+    fn draw_at(&self, pos: Vector2, draw: &mut impl RaylibDraw);
+}
+
+impl<T: TimedPickup> Pickup for T {
+    fn name(&self) -> &'static str {
+        TimedPickup::name(self)
+    }
+
+    fn pos(&self) -> Vector2 {
+        self.timed().pos
+    }
+
+    fn size(&self) -> Vector2 {
+        Vector2::one() * self.timed().size
+    }
+
+    fn is_alive(&self) -> bool {
+        self.timed().lifetime > 0.0
+    }
+
+    fn collect(&mut self, ctx: &mut PickupContext) {
+        self.apply(ctx);
+        self.timed_mut().lifetime = 0.0;
+    }
+
+    fn attract(&mut self, target: Vector2) {
+        let timed = self.timed_mut();
+        home(&mut timed.pos, &mut timed.velocity, target);
+    }
+
+    fn update(&mut self, frame_info: FrameInfo, _ctx: &mut PickupContext) {
+        self.timed_mut().lifetime -= frame_info.delta_time;
+        self.animate(frame_info);
+    }
+
+    fn draw(&self, draw: &mut impl RaylibDraw) {
+        if !is_blinking(self.timed().lifetime) {
+            self.draw_at(self.timed().pos, draw);
+        }
+    }
+}
+
+#[enum_dispatch]
+#[derive(Debug)]
+pub enum AnyPickup {
+    Bomb,
+    Shield,
+    SlowMotion,
+    Rainbow,
+    WidePad,
+    Magnet
+}
+
+/// When and how a kind of pickup appears. Only one pickup can be on screen at once
+pub struct SpawnRule {
+    /// Score the player needs before the pickup can appear
+    pub min_score: u64,
+    /// On every reference frame, the pickup appears with a 1 in `probability + 1` chance
+    pub probability: u16,
+    pub size: f32,
+    /// Creates the pickup, given its top left corner
+    pub spawn: fn(Vector2) -> AnyPickup
+}
+
+/// Rolled in order, so the first ones take precedence when several would spawn on the same frame
+pub const SPAWN_RULES: &[SpawnRule] = &[
+    SpawnRule {
+        min_score: PLAYER_COUNT_BOMB,
+        probability: BOMB_PROBABILITY,
+        size: BOMB_SIZE,
+        spawn: |pos| Bomb::new(pos).into()
+    },
+    SpawnRule {
+        min_score: PLAYER_COUNT_SHIELD,
+        probability: SHIELD_PROBABILITY,
+        size: SHIELD_SIZE,
+        spawn: |pos| Shield::new(pos).into()
+    },
+    SpawnRule {
+        min_score: PLAYER_COUNT_SLOW_MOTION,
+        probability: SLOW_MOTION_PROBABILITY,
+        size: SLOW_MOTION_SIZE,
+        spawn: |pos| SlowMotion::new(pos).into()
+    },
+    SpawnRule {
+        min_score: PLAYER_COUNT_RAINBOW,
+        probability: RAINBOW_PROBABILITY,
+        size: RAINBOW_SIZE,
+        spawn: |pos| Rainbow::new(pos).into()
+    },
+    SpawnRule {
+        min_score: PLAYER_COUNT_WIDE_PAD,
+        probability: WIDE_PAD_PROBABILITY,
+        size: WIDE_PAD_SIZE,
+        spawn: |pos| WidePad::new(pos).into()
+    },
+    SpawnRule {
+        min_score: PLAYER_COUNT_MAGNET,
+        probability: MAGNET_PROBABILITY,
+        size: MAGNET_SIZE,
+        spawn: |pos| Magnet::new(pos).into()
+    }
+];
\ No newline at end of file
@@ -0,0 +1,76 @@
+/*
+Copyright (C) 2025 Amari Calipso
+
+This file is part of !pong.
+
+!pong is free software: you can redistribute it and/or modify
+it under the terms of the GNU Affero General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+!pong is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Affero General Public License for more details.
+
+You should have received a copy of the GNU Affero General Public License
+along with !pong.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+
+use raylib::{color::Color, math::{Rectangle, Vector2}, prelude::RaylibDraw};
+
+use crate::{pickup::{PickupContext, Timed, TimedPickup}, FrameInfo, HOVER_RAINBOW_DISTANCE, RAINBOW_DELTA, RAINBOW_LIFE, RAINBOW_SIZE};
+
+/// Makes the player invincible for a while, smashing the obstacles it touches
+#[derive(Debug)]
+pub struct Rainbow {
+    timed: Timed,
+    color_cnt: f32
+}
+
+impl Rainbow {
+    pub fn new(pos: Vector2) -> Self {
+        Self {
+            timed: Timed::new(pos, RAINBOW_SIZE, RAINBOW_LIFE),
+            color_cnt: 0.0
+        }
+    }
+}
+
+impl TimedPickup for Rainbow {
+    fn name(&self) -> &'static str {
+        "rainbow"
+    }
+
+    fn timed(&self) -> &Timed {
+        &self.timed
+    }
+
+    fn timed_mut(&mut self) -> &mut Timed {
+        &mut self.timed
+    }
+
+    fn apply(&mut self, ctx: &mut PickupContext) {
+        ctx.player.start_rainbow();
+    }
+
+    fn animate(&mut self, frame_info: FrameInfo) {
+        self.color_cnt += RAINBOW_DELTA * frame_info.delta_time;
+        if self.color_cnt > 1.0 {
+            self.color_cnt = 0.0;
+        }
+    }
+
+    fn draw_at(&self, pos: Vector2, draw: &mut impl RaylibDraw) {
+        let color = |offset: f32| Color::color_from_hsv((self.color_cnt + offset * HOVER_RAINBOW_DISTANCE * 4.0) * 360.0, 1.0, 1.0);
+
+        draw.draw_rectangle_gradient_ex(
+            Rectangle::new(
+                pos.x, pos.y, 
+                RAINBOW_SIZE, RAINBOW_SIZE, 
+            ),
+            color(0.0), color(1.0), color(2.0), color(3.0),
+        );
+    }
+}
\ No newline at end of file
@@ -0,0 +1,58 @@
+/*
+Copyright (C) 2025 Amari Calipso
+
+This file is part of !pong.
+
+!pong is free software: you can redistribute it and/or modify
+it under the terms of the GNU Affero General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+!pong is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Affero General Public License for more details.
+
+You should have received a copy of the GNU Affero General Public License
+along with !pong.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+
+use raylib::{math::Vector2, prelude::RaylibDraw};
+
+use crate::{pickup::{PickupContext, Timed, TimedPickup}, utils::vec2, FG, SLOW_MOTION_DURATION, SLOW_MOTION_LIFE, SLOW_MOTION_SIZE};
+
+/// Slows obstacles down for a while, leaving the player's speed untouched
+#[derive(Debug)]
+pub struct SlowMotion(Timed);
+
+impl SlowMotion {
+    pub fn new(pos: Vector2) -> Self {
+        Self(Timed::new(pos, SLOW_MOTION_SIZE, SLOW_MOTION_LIFE))
+    }
+}
+
+impl TimedPickup for SlowMotion {
+    fn name(&self) -> &'static str {
+        "slow_motion"
+    }
+
+    fn timed(&self) -> &Timed {
+        &self.0
+    }
+
+    fn timed_mut(&mut self) -> &mut Timed {
+        &mut self.0
+    }
+
+    fn apply(&mut self, ctx: &mut PickupContext) {
+        ctx.effects.slow_motion = SLOW_MOTION_DURATION;
+    }
+
+    fn draw_at(&self, pos: Vector2, draw: &mut impl RaylibDraw) {
+        // hourglass
+        let center = pos + Vector2::one() * (SLOW_MOTION_SIZE / 2.0);
+        draw.draw_triangle(pos, center, vec2(pos.x + SLOW_MOTION_SIZE, pos.y), FG);
+        draw.draw_triangle(vec2(pos.x, pos.y + SLOW_MOTION_SIZE), pos + Vector2::one() * SLOW_MOTION_SIZE, center, FG);
+    }
+}
\ No newline at end of file
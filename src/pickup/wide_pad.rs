@@ -0,0 +1,68 @@
+/*
+Copyright (C) 2025 Amari Calipso
+
+This file is part of !pong.
+
+!pong is free software: you can redistribute it and/or modify
+it under the terms of the GNU Affero General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+!pong is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Affero General Public License for more details.
+
+You should have received a copy of the GNU Affero General Public License
+along with !pong.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+
+use raylib::{math::Vector2, prelude::RaylibDraw};
+
+use crate::{pickup::{PickupContext, Timed, TimedPickup}, FG, WIDE_PAD_HEIGHT, WIDE_PAD_HITS, WIDE_PAD_LIFE, WIDE_PAD_SIZE};
+
+/// Enlarges both pads for the next few hits
+#[derive(Debug)]
+pub struct WidePad(Timed);
+
+impl WidePad {
+    pub fn new(pos: Vector2) -> Self {
+        Self(Timed::new(pos, WIDE_PAD_SIZE, WIDE_PAD_LIFE))
+    }
+}
+
+impl TimedPickup for WidePad {
+    fn name(&self) -> &'static str {
+        "wide_pad"
+    }
+
+    fn timed(&self) -> &Timed {
+        &self.0
+    }
+
+    fn timed_mut(&mut self) -> &mut Timed {
+        &mut self.0
+    }
+
+    fn apply(&mut self, ctx: &mut PickupContext) {
+        *ctx.custom_pad_count = WIDE_PAD_HITS;
+        ctx.left_pad.resize(WIDE_PAD_HEIGHT);
+        ctx.right_pad.resize(WIDE_PAD_HEIGHT);
+    }
+
+    fn draw_at(&self, pos: Vector2, draw: &mut impl RaylibDraw) {
+        // two tall bars, like the pads
+        draw.draw_rectangle(
+            pos.x as i32, pos.y as i32, 
+            2, WIDE_PAD_SIZE as i32, 
+            FG
+        );
+
+        draw.draw_rectangle(
+            (pos.x + WIDE_PAD_SIZE - 2.0) as i32, pos.y as i32, 
+            2, WIDE_PAD_SIZE as i32, 
+            FG
+        );
+    }
+}
\ No newline at end of file
@@ -17,10 +17,35 @@ You should have received a copy of the GNU Affero General Public License
 along with !pong.  If not, see <http://www.gnu.org/licenses/>.
  */
 
-use rand::{rngs::ThreadRng, Rng};
+use rand::{rngs::StdRng, Rng};
 use raylib::{color::Color, math::{Rectangle, Vector2}, prelude::RaylibDraw};
 
-use crate::{explosion::Explosion, pad::Pad, FrameInfo, DEATH_MAX_INIT_PARTICLE_VELOCITY, FG, GRAVITY, HOVER_RAINBOW_DELTA, HOVER_RAINBOW_DISTANCE, HOVER_SPACE, INTERNAL_RESOLUTION, JUMP_VELOCITY, PLAYER_SIZE, PLAYER_VELOCITY, RAINBOW_DELTA, SPRINT_VELOCITY};
+use crate::{explosion::Explosion, pad::Pad, FrameInfo, DEATH_MAX_INIT_PARTICLE_VELOCITY, FG, GRAVITY, GRAVITY_WELL_MIN_SPEED, GRAVITY_WELL_SPEED_RECOVERY, HOVER_RAINBOW_DELTA, HOVER_RAINBOW_DISTANCE, HOVER_SPACE, INTERNAL_RESOLUTION, JUMP_VELOCITY, PLAYER_SIZE, PLAYER_VELOCITY, RAINBOW_DELTA, RAINBOW_DURATION, RAINBOW_FLASH_TIME, RESPAWN_BLINK_TIME, RESPAWN_INVULNERABILITY, SHIELD_OUTLINE_DISTANCE, SPAWN_PREDICTION_FRAMES, SPRINT_VELOCITY};
+
+/// What killed the player, as reported by `check_death`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeathCause {
+    LeftWall, RightWall, Ceiling, Floor, Rock, Rocket, Missile, Laser, GravityWell, Mine, Asteroid, Barrier
+}
+
+impl DeathCause {
+    pub fn name(self) -> &'static str {
+        match self {
+            DeathCause::LeftWall => "left_wall",
+            DeathCause::RightWall => "right_wall",
+            DeathCause::Ceiling => "ceiling",
+            DeathCause::Floor => "floor",
+            DeathCause::Rock => "rock",
+            DeathCause::Rocket => "rocket",
+            DeathCause::Missile => "missile",
+            DeathCause::Laser => "laser",
+            DeathCause::GravityWell => "gravity_well",
+            DeathCause::Mine => "mine",
+            DeathCause::Asteroid => "asteroid",
+            DeathCause::Barrier => "barrier",
+        }
+    }
+}
 
 #[derive(Debug)]
 pub struct Player {
@@ -29,18 +54,28 @@ pub struct Player {
     pub count: u64,
     pub playing: bool,
     pub sprinting: bool,
-    pub dead: bool,
+    /// Whether the next rock or rocket hit is absorbed
+    pub shielded: bool,
+    /// Set when the player hits an obstacle, so it dies on the next check
+    pub dead: Option<DeathCause>,
+    /// Whether the player is invincible to obstacles, smashing them on contact
     rainbow: bool,
     rainbow_cnt: f32,
+    /// Frames left before the rainbow mode ends
+    rainbow_time: f32,
+    /// Frames left before obstacles can hurt the player again, after losing a life
+    respawn_time: f32,
 
     pub pos: Vector2,
     velocity: Vector2,
+    /// Acceleration from gravity wells, on top of `GRAVITY`
+    force: Vector2,
 
     pub explosion: Explosion
 }
 
 impl Player {
-    fn base_velocity(rng: &mut ThreadRng) -> Vector2 {
+    fn base_velocity(rng: &mut StdRng) -> Vector2 {
         if rng.random_bool(0.5) {
             PLAYER_VELOCITY
         } else {
@@ -59,16 +94,20 @@ impl Player {
             count: 0,
             playing: false,
             sprinting: false,
-            dead: false,
+            shielded: false,
+            dead: None,
             rainbow: false,
             rainbow_cnt: 0.0,
+            rainbow_time: 0.0,
+            respawn_time: 0.0,
             pos,
             velocity: Vector2::zero(),
+            force: Vector2::zero(),
             explosion: Explosion::new(pos),
         }  
     }
 
-    pub fn init(&mut self, rng: &mut ThreadRng) {
+    pub fn init(&mut self, rng: &mut StdRng) {
         self.velocity = Self::base_velocity(rng);
     }
 
@@ -79,19 +118,95 @@ impl Player {
         };
     }
 
-    pub fn reset(&mut self, rng: &mut ThreadRng) {
+    pub fn reset(&mut self, rng: &mut StdRng) {
         self.hover_angle = 0.0;
         self.count = 0;
         self.playing = false;
-        self.dead = false;
+        self.dead = None;
         self.sprinting = false;
+        self.shielded = false;
         self.rainbow = false;
         self.rainbow_cnt = 0.0;
+        self.rainbow_time = 0.0;
+        self.respawn_time = 0.0;
+
+        self.velocity = Self::base_velocity(rng);
+        self.reset_pos();
+    }
+
+    /// Puts the player back at the center after losing a life, keeping its score. 
+    /// It waits there for the death explosion to end, then stays invulnerable for a while
+    pub fn respawn(&mut self, rng: &mut StdRng) {
+        self.dead = None;
+        self.sprinting = false;
+        self.respawn_time = RESPAWN_INVULNERABILITY;
 
         self.velocity = Self::base_velocity(rng);
         self.reset_pos();
     }
 
+    pub fn is_invulnerable(&self) -> bool {
+        self.respawn_time > 0.0
+    }
+
+    pub fn velocity(&self) -> Vector2 {
+        self.velocity
+    }
+
+    /// Sets the acceleration gravity wells apply on the next update
+    pub fn pull(&mut self, force: Vector2) {
+        self.force = force;
+    }
+
+    /// Where the player is expected to be over the next `SPAWN_PREDICTION_FRAMES` frames, 
+    /// assuming it bounces off the pads and keeps jumping to hold its current height
+    pub fn predict_path(&self, left_pad: &Pad, right_pad: &Pad) -> Vec<Vector2> {
+        let mut pos = self.pos;
+        let mut velocity = self.velocity;
+
+        let mut path = Vec::with_capacity(SPAWN_PREDICTION_FRAMES as usize + 1);
+        path.push(pos);
+
+        for _ in 0 .. SPAWN_PREDICTION_FRAMES {
+            if self.sprinting {
+                pos.x += SPRINT_VELOCITY.copysign(velocity.x);
+            } else {
+                if pos.y > self.pos.y && velocity.y > 0.0 {
+                    velocity.y = -JUMP_VELOCITY;
+                }
+
+                velocity += GRAVITY;
+                pos += velocity;
+            }
+
+            if (velocity.x < 0.0 && pos.x <= left_pad.pos.x + left_pad.size().x) || 
+               (velocity.x > 0.0 && pos.x + PLAYER_SIZE >= right_pad.pos.x) 
+            {
+                velocity.x = -velocity.x;
+            }
+
+            path.push(pos);
+        }
+
+        path
+    }
+
+    pub fn is_rainbow(&self) -> bool {
+        self.rainbow
+    }
+
+    pub fn start_rainbow(&mut self) {
+        self.rainbow = true;
+        self.rainbow_time = RAINBOW_DURATION;
+    }
+
+    fn cycle_rainbow(&mut self, delta: f32) {
+        self.rainbow_cnt += delta;
+        if self.rainbow_cnt > 1.0 {
+            self.rainbow_cnt = 0.0;
+        }
+    }
+
     pub fn sprint_on(&mut self) {
         self.sprinting = true;
         self.velocity.y = 0.0;
@@ -101,7 +216,7 @@ impl Player {
         self.sprinting = false;
     }
 
-    pub fn start(&mut self, rng: &mut ThreadRng) {
+    pub fn start(&mut self, rng: &mut StdRng) {
         self.playing = true;
         self.reset_pos();
         self.velocity = Self::base_velocity(rng);
@@ -112,7 +227,7 @@ impl Player {
         self.velocity.x = -self.velocity.x;
     }
 
-    pub fn jump(&mut self, rng: &mut ThreadRng) {
+    pub fn jump(&mut self, rng: &mut StdRng) {
         if !self.playing {
             self.start(rng);
         }
@@ -122,19 +237,21 @@ impl Player {
         }
     }
 
-    pub fn is_dead(&mut self, left_pad: &Pad, right_pad: &Pad, tolerance: f32, rng: &mut ThreadRng) -> bool {
-        if self.dead {
+    /// Checks whether the player died, exploding it if so. It's up to the caller to reset or respawn it
+    pub fn check_death(&mut self, left_pad: &Pad, right_pad: &Pad, tolerance: f32, rng: &mut StdRng) -> Option<DeathCause> {
+        if let Some(cause) = self.dead {
             self.explosion.explode_with_pos(
                 self.pos, 
                 DEATH_MAX_INIT_PARTICLE_VELOCITY, 
                 false, rng
             );
 
-            self.reset(rng);
-            return true;
+            return Some(cause);
         }
 
+        let cause;
         if self.pos.x <= 0.0 {
+            cause = DeathCause::LeftWall;
             // avoids collision problems with low framerate
             if !left_pad.collides(self.pos, tolerance) {
                 self.explosion.explode_with_pos(
@@ -144,6 +261,7 @@ impl Player {
                 );
             }
         } else if self.pos.x + PLAYER_SIZE >= INTERNAL_RESOLUTION.x {
+            cause = DeathCause::RightWall;
             self.explosion.explode_with_pos(
                 Vector2 { 
                     x: INTERNAL_RESOLUTION.x - 1.0, 
@@ -153,12 +271,14 @@ impl Player {
                 false, rng
             );
         } else if self.pos.y <= 0.0 {
+            cause = DeathCause::Ceiling;
             self.explosion.explode_with_pos(
                 Vector2 { x: self.pos.x, y: 0.0 }, 
                 DEATH_MAX_INIT_PARTICLE_VELOCITY, 
                 false, rng
             );
         } else if self.pos.y + PLAYER_SIZE >= INTERNAL_RESOLUTION.y {
+            cause = DeathCause::Floor;
             // avoids collision problems with low framerate
             if !right_pad.collides(self.pos, tolerance) {
                 self.explosion.explode_with_pos(
@@ -171,11 +291,10 @@ impl Player {
                 );
             }
         } else {
-            return false;
+            return None;
         }
 
-        self.reset(rng);
-        true
+        Some(cause)
     }
 
     fn dir(&self, x: f32) -> f32 {
@@ -186,88 +305,133 @@ impl Player {
         }
     }
 
-    pub fn update(&mut self, frame_info: FrameInfo, rng: &mut ThreadRng, draw: &mut impl RaylibDraw) {
+    pub fn update(&mut self, frame_info: FrameInfo) {
         if self.explosion.is_alive() {
             self.explosion.update(frame_info);
-            self.explosion.show(draw);
-        } else {
-            if self.playing {
-                if self.sprinting {
-                    self.pos.x += self.dir(SPRINT_VELOCITY) * frame_info.delta_time;
-                } else {
-                    self.velocity += GRAVITY * frame_info.delta_time;
-                    self.pos += self.velocity * frame_info.delta_time;
-                }
-
-                if self.sprinting {
-                    draw.draw_rectangle(
-                        self.pos.x as i32, self.pos.y as i32, 
-                        PLAYER_SIZE as i32, PLAYER_SIZE as i32, 
-                        Color::color_from_hsv(self.rainbow_cnt * 360.0, 1.0, 1.0)
-                    );
-
-                    self.rainbow_cnt += RAINBOW_DELTA * frame_info.delta_time;
-                    if self.rainbow_cnt > 1.0 {
-                        self.rainbow_cnt = 0.0;
-                    }
-                } else {
-                    draw.draw_rectangle(
-                        self.pos.x as i32, self.pos.y as i32, 
-                        PLAYER_SIZE as i32, PLAYER_SIZE as i32, 
-                        FG
-                    );
-                }
+        } else if self.playing {
+            if self.sprinting {
+                self.pos.x += self.dir(SPRINT_VELOCITY) * frame_info.delta_time;
+                self.cycle_rainbow(RAINBOW_DELTA * frame_info.delta_time);
             } else {
-                // this shouldn't happen, but it does and i have no idea why
-                if self.velocity.x as i32 == 0 {
-                    if self.pos.x < INTERNAL_RESOLUTION.x / 2.0 {
-                        self.velocity = PLAYER_VELOCITY;
-                    } else {
-                        self.velocity = -PLAYER_VELOCITY;
-                    }
-                }
-
-                if self.pos.y > INTERNAL_RESOLUTION.y / 2.0 + HOVER_SPACE {
-                    self.velocity.y = -JUMP_VELOCITY;
-                }
+                let dir = self.dir(1.0);
+                self.velocity += (GRAVITY + self.force) * frame_info.delta_time;
 
-                if self.pos.x + PLAYER_SIZE + frame_info.tolerance >= INTERNAL_RESOLUTION.x || self.pos.x <= frame_info.tolerance {
-                    self.velocity.x = -self.velocity.x;
-                }
+                // wells bend the horizontal speed too, but never turn the player around, and it slowly goes back to normal
+                let speed = (self.velocity.x * dir).max(GRAVITY_WELL_MIN_SPEED);
+                self.velocity.x = dir * (speed + (PLAYER_VELOCITY.x - speed) * GRAVITY_WELL_SPEED_RECOVERY * frame_info.delta_time);
 
-                self.velocity += GRAVITY * frame_info.delta_time;
                 self.pos += self.velocity * frame_info.delta_time;
 
-                let mut color0 = Color::color_from_hsv(self.rainbow_cnt * 360.0, 1.0, 1.0);
-                let mut color1 = Color::color_from_hsv((self.rainbow_cnt - HOVER_RAINBOW_DISTANCE) * 360.0, 1.0, 1.0);
+                if self.rainbow {
+                    self.cycle_rainbow(RAINBOW_DELTA * frame_info.delta_time);
+                }
+            }
 
-                if rng.random_bool(0.5) {
-                    std::mem::swap(&mut color0, &mut color1);
+            if self.rainbow {
+                self.rainbow_time -= frame_info.delta_time;
+                if self.rainbow_time <= 0.0 {
+                    self.rainbow = false;
                 }
+            }
 
-                let color2;
-                let color3;
-                if rng.random_bool(0.5) {
-                    color2 = color0;
-                    color3 = color1;
+            self.respawn_time = (self.respawn_time - frame_info.delta_time).max(0.0);
+        } else {
+            // this shouldn't happen, but it does and i have no idea why
+            if self.velocity.x as i32 == 0 {
+                if self.pos.x < INTERNAL_RESOLUTION.x / 2.0 {
+                    self.velocity = PLAYER_VELOCITY;
                 } else {
-                    color2 = color1;
-                    color3 = color0;
+                    self.velocity = -PLAYER_VELOCITY;
                 }
+            }
+
+            if self.pos.y > INTERNAL_RESOLUTION.y / 2.0 + HOVER_SPACE {
+                self.velocity.y = -JUMP_VELOCITY;
+            }
+
+            if self.pos.x + PLAYER_SIZE + frame_info.tolerance >= INTERNAL_RESOLUTION.x || self.pos.x <= frame_info.tolerance {
+                self.velocity.x = -self.velocity.x;
+            }
+
+            self.velocity += GRAVITY * frame_info.delta_time;
+            self.pos += self.velocity * frame_info.delta_time;
+
+            self.cycle_rainbow(HOVER_RAINBOW_DELTA * frame_info.delta_time);
+        }
+    }
+
+    fn draw_gradient(&self, draw: &mut impl RaylibDraw) {
+        let mut color0 = Color::color_from_hsv(self.rainbow_cnt * 360.0, 1.0, 1.0);
+        let mut color1 = Color::color_from_hsv((self.rainbow_cnt - HOVER_RAINBOW_DISTANCE) * 360.0, 1.0, 1.0);
+
+        // purely cosmetic, so it doesn't use the game's generator
+        let mut rng = rand::rng();
+        if rng.random_bool(0.5) {
+            std::mem::swap(&mut color0, &mut color1);
+        }
 
-                draw.draw_rectangle_gradient_ex(
-                    Rectangle::new(
-                        self.pos.x, self.pos.y, 
-                        PLAYER_SIZE, PLAYER_SIZE, 
-                    ),
-                    color0, color1, color2, color3,
+        let color2;
+        let color3;
+        if rng.random_bool(0.5) {
+            color2 = color0;
+            color3 = color1;
+        } else {
+            color2 = color1;
+            color3 = color0;
+        }
+
+        draw.draw_rectangle_gradient_ex(
+            Rectangle::new(
+                self.pos.x, self.pos.y, 
+                PLAYER_SIZE, PLAYER_SIZE, 
+            ),
+            color0, color1, color2, color3,
+        );
+    }
+
+    pub fn draw(&self, draw: &mut impl RaylibDraw) {
+        if self.explosion.is_alive() {
+            self.explosion.show(draw);
+
+            // while playing, it's from a lost life, with the player waiting at the center
+            if !self.playing {
+                return;
+            }
+        }
+
+        if self.playing {
+            if self.is_invulnerable() && (self.respawn_time / RESPAWN_BLINK_TIME) as i32 % 2 == 0 {
+                return;
+            }
+
+            // flashes when it's about to end
+            let flashing = self.rainbow_time < RAINBOW_FLASH_TIME && (self.rainbow_time / (RAINBOW_FLASH_TIME / 8.0)) as i32 % 2 == 0;
+
+            if self.rainbow && !flashing {
+                self.draw_gradient(draw);
+            } else if self.sprinting {
+                draw.draw_rectangle(
+                    self.pos.x as i32, self.pos.y as i32, 
+                    PLAYER_SIZE as i32, PLAYER_SIZE as i32, 
+                    Color::color_from_hsv(self.rainbow_cnt * 360.0, 1.0, 1.0)
+                );
+            } else {
+                draw.draw_rectangle(
+                    self.pos.x as i32, self.pos.y as i32, 
+                    PLAYER_SIZE as i32, PLAYER_SIZE as i32, 
+                    FG
                 );
+            }
 
-                self.rainbow_cnt += HOVER_RAINBOW_DELTA * frame_info.delta_time;
-                if self.rainbow_cnt > 1.0 {
-                    self.rainbow_cnt = 0.0;
-                }
+            if self.shielded {
+                draw.draw_rectangle_lines(
+                    (self.pos.x - SHIELD_OUTLINE_DISTANCE) as i32, (self.pos.y - SHIELD_OUTLINE_DISTANCE) as i32, 
+                    (PLAYER_SIZE + SHIELD_OUTLINE_DISTANCE * 2.0) as i32, (PLAYER_SIZE + SHIELD_OUTLINE_DISTANCE * 2.0) as i32, 
+                    FG
+                );
             }
+        } else {
+            self.draw_gradient(draw);
         }
     }
 }
\ No newline at end of file
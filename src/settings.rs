@@ -0,0 +1,356 @@
+/*
+Copyright (C) 2025 Amari Calipso
+
+This file is part of !pong.
+
+!pong is free software: you can redistribute it and/or modify
+it under the terms of the GNU Affero General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+!pong is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Affero General Public License for more details.
+
+You should have received a copy of the GNU Affero General Public License
+along with !pong.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use std::{env, fs, io::Error, path::PathBuf};
+
+use raylib::{color::Color, window::{get_current_monitor, get_monitor_refresh_rate}};
+
+use crate::{input::{Action, Binding, Bindings, TouchLayout}, BG, FG, MAX_LIVES, SETTINGS_FILENAME, SETTINGS_FOLDER, SHAKE_STEP, MAX_SHAKE, VOLUME_STEP};
+
+fn cycle<T: Copy + PartialEq>(all: &[T], curr: T, delta: i32) -> T {
+    let idx = all.iter().position(|x| *x == curr).unwrap_or(0) as i32;
+    all[(idx + delta).rem_euclid(all.len() as i32) as usize]
+}
+
+fn parse<T: Copy>(all: &[T], label: impl Fn(T) -> String, value: &str) -> Option<T> {
+    all.iter().copied().find(|x| label(*x).eq_ignore_ascii_case(value))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DitherMode {
+    Off, Ordered4, Ordered8
+}
+
+impl DitherMode {
+    const ALL: [DitherMode; 3] = [DitherMode::Off, DitherMode::Ordered4, DitherMode::Ordered8];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            DitherMode::Off => "OFF",
+            DitherMode::Ordered4 => "4X4",
+            DitherMode::Ordered8 => "8X8",
+        }
+    }
+
+    /// Size of the threshold matrix to use, if any
+    pub fn matrix_size(self) -> Option<usize> {
+        match self {
+            DitherMode::Off => None,
+            DitherMode::Ordered4 => Some(4),
+            DitherMode::Ordered8 => Some(8),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Theme {
+    Classic, Inverted, Amber, Phosphor
+}
+
+impl Theme {
+    const ALL: [Theme; 4] = [Theme::Classic, Theme::Inverted, Theme::Amber, Theme::Phosphor];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Theme::Classic => "CLASSIC",
+            Theme::Inverted => "INVERTED",
+            Theme::Amber => "AMBER",
+            Theme::Phosphor => "PHOSPHOR",
+        }
+    }
+
+    /// Color the game's foreground gets mapped to
+    pub fn fg(self) -> Color {
+        match self {
+            Theme::Classic => FG,
+            Theme::Inverted => BG,
+            Theme::Amber => Color { r: 255, g: 176, b: 0, a: 255 },
+            Theme::Phosphor => Color { r: 51, g: 255, b: 102, a: 255 },
+        }
+    }
+
+    /// Color the game's background gets mapped to
+    pub fn bg(self) -> Color {
+        match self {
+            Theme::Classic => BG,
+            Theme::Inverted => FG,
+            Theme::Amber => Color { r: 28, g: 16, b: 0, a: 255 },
+            Theme::Phosphor => Color { r: 0, g: 20, b: 8, a: 255 },
+        }
+    }
+
+    /// Color of the game box
+    pub fn border(self) -> Color {
+        match self {
+            Theme::Classic | Theme::Inverted => Color::BLUEVIOLET,
+            Theme::Amber | Theme::Phosphor => self.fg(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FpsCap {
+    Monitor,
+    Fixed(u32),
+    Unlimited
+}
+
+impl FpsCap {
+    const ALL: [FpsCap; 7] = [
+        FpsCap::Monitor, FpsCap::Fixed(30), FpsCap::Fixed(60), FpsCap::Fixed(120),
+        FpsCap::Fixed(144), FpsCap::Fixed(240), FpsCap::Unlimited
+    ];
+
+    pub fn label(self) -> String {
+        match self {
+            FpsCap::Monitor => String::from("MONITOR"),
+            FpsCap::Fixed(fps) => fps.to_string(),
+            FpsCap::Unlimited => String::from("UNLIMITED"),
+        }
+    }
+
+    /// Value to pass to `set_target_fps` (0 means unlimited)
+    pub fn target_fps(self) -> u32 {
+        match self {
+            FpsCap::Monitor => get_monitor_refresh_rate(get_current_monitor()) as u32,
+            FpsCap::Fixed(fps) => fps,
+            FpsCap::Unlimited => 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettingsEntry {
+    Volume, Dithering, Theme, Shake, FpsCap, Fullscreen, ShowFps, Touch, Lives, Controls, Back
+}
+
+impl SettingsEntry {
+    pub const ALL: [SettingsEntry; 11] = [
+        SettingsEntry::Volume, SettingsEntry::Dithering, SettingsEntry::Theme, SettingsEntry::Shake,
+        SettingsEntry::FpsCap, SettingsEntry::Fullscreen, SettingsEntry::ShowFps, SettingsEntry::Touch,
+        SettingsEntry::Lives, SettingsEntry::Controls, SettingsEntry::Back
+    ];
+}
+
+#[derive(Debug, Clone)]
+pub struct Settings {
+    /// Master volume, in percent
+    pub volume: u8,
+    pub dithering: DitherMode,
+    pub theme: Theme,
+    /// Screen shake intensity, in percent of `SHAKE`
+    pub shake: u8,
+    pub fps_cap: FpsCap,
+    pub fullscreen: bool,
+    pub show_fps: bool,
+    /// Layout of the on-screen touch zones
+    pub touch: TouchLayout,
+    /// Lives per game, from 1 to `MAX_LIVES`. Changes apply from the next game
+    pub lives: u32,
+    pub bindings: Bindings
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            volume: 100,
+            dithering: DitherMode::Ordered8,
+            theme: Theme::Classic,
+            shake: 100,
+            fps_cap: FpsCap::Monitor,
+            fullscreen: false,
+            show_fps: false,
+            touch: TouchLayout::Off,
+            lives: 1,
+            bindings: Bindings::default()
+        }
+    }
+}
+
+impl Settings {
+    fn path() -> PathBuf {
+        let base = {
+            if cfg!(windows) {
+                env::var_os("APPDATA").map(PathBuf::from)
+            } else {
+                env::var_os("XDG_CONFIG_HOME").map(PathBuf::from)
+                    .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+            }
+        };
+
+        base.unwrap_or_default().join(SETTINGS_FOLDER).join(SETTINGS_FILENAME)
+    }
+
+    /// Loads settings from disk. Missing or invalid values are replaced with defaults
+    pub fn load() -> Self {
+        let mut settings = Self::default();
+
+        let Ok(contents) = fs::read_to_string(Self::path()) else {
+            return settings;
+        };
+
+        // saved bindings can move keys between actions, so none of the defaults
+        // may hold on to them while the file is read
+        settings.bindings.clear();
+
+        for line in contents.lines() {
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+
+            settings.set(key.trim(), value.trim());
+        }
+
+        settings.bindings.fill_defaults();
+        settings
+    }
+
+    fn set(&mut self, key: &str, value: &str) {
+        match key {
+            "volume" => {
+                if let Ok(volume) = value.parse::<u8>() {
+                    self.volume = volume.min(100);
+                }
+            }
+            "dithering" => {
+                if let Some(mode) = parse(&DitherMode::ALL, |x| x.label().to_string(), value) {
+                    self.dithering = mode;
+                }
+            }
+            "theme" => {
+                if let Some(theme) = parse(&Theme::ALL, |x| x.label().to_string(), value) {
+                    self.theme = theme;
+                }
+            }
+            "shake" => {
+                if let Ok(shake) = value.parse::<u8>() {
+                    self.shake = shake.min(MAX_SHAKE);
+                }
+            }
+            "fps_cap" => {
+                if let Some(cap) = parse(&FpsCap::ALL, FpsCap::label, value) {
+                    self.fps_cap = cap;
+                }
+            }
+            "fullscreen" => {
+                if let Ok(fullscreen) = value.parse() {
+                    self.fullscreen = fullscreen;
+                }
+            }
+            "show_fps" => {
+                if let Ok(show_fps) = value.parse() {
+                    self.show_fps = show_fps;
+                }
+            }
+            "touch" => {
+                if let Some(layout) = parse(&TouchLayout::ALL, |x| x.label().to_string(), value) {
+                    self.touch = layout;
+                }
+            }
+            "lives" => {
+                if let Ok(lives) = value.parse::<u32>() {
+                    self.lives = lives.clamp(1, MAX_LIVES);
+                }
+            }
+            _ => {
+                let action = key.strip_prefix("bind_")
+                    .and_then(|name| Action::ALL.into_iter().find(|action| action.name() == name));
+
+                if let Some(action) = action {
+                    let bindings: Vec<Binding> = value.split(',')
+                        .filter_map(|name| Binding::parse(name.trim()))
+                        .collect();
+
+                    if !bindings.is_empty() {
+                        self.bindings.set(action, bindings);
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn save(&self) -> Result<(), Error> {
+        let path = Self::path();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut contents = String::new();
+        contents += &format!("volume={}\n", self.volume);
+        contents += &format!("dithering={}\n", self.dithering.label());
+        contents += &format!("theme={}\n", self.theme.label());
+        contents += &format!("shake={}\n", self.shake);
+        contents += &format!("fps_cap={}\n", self.fps_cap.label());
+        contents += &format!("fullscreen={}\n", self.fullscreen);
+        contents += &format!("show_fps={}\n", self.show_fps);
+        contents += &format!("touch={}\n", self.touch.label());
+        contents += &format!("lives={}\n", self.lives);
+
+        for action in Action::ALL {
+            let bindings: Vec<&str> = self.bindings.get(action).iter().map(|x| x.name()).collect();
+            contents += &format!("bind_{}={}\n", action.name(), bindings.join(","));
+        }
+
+        fs::write(path, contents)
+    }
+
+    pub fn label(&self, entry: SettingsEntry) -> String {
+        fn on_off(value: bool) -> &'static str {
+            if value { "ON" } else { "OFF" }
+        }
+
+        match entry {
+            SettingsEntry::Volume => format!("VOLUME: {}%", self.volume),
+            SettingsEntry::Dithering => format!("DITHERING: {}", self.dithering.label()),
+            SettingsEntry::Theme => format!("THEME: {}", self.theme.label()),
+            SettingsEntry::Shake => format!("SHAKE: {}%", self.shake),
+            SettingsEntry::FpsCap => format!("FPS CAP: {}", self.fps_cap.label()),
+            SettingsEntry::Fullscreen => format!("FULLSCREEN: {}", on_off(self.fullscreen)),
+            SettingsEntry::ShowFps => format!("SHOW FPS: {}", on_off(self.show_fps)),
+            SettingsEntry::Touch => format!("TOUCH: {}", self.touch.label()),
+            SettingsEntry::Lives => format!("LIVES: {}", self.lives),
+            SettingsEntry::Controls => String::from("CONTROLS"),
+            SettingsEntry::Back => String::from("BACK"),
+        }
+    }
+
+    /// Changes the value of `entry` by `delta` steps, wrapping around
+    pub fn adjust(&mut self, entry: SettingsEntry, delta: i32) {
+        match entry {
+            SettingsEntry::Volume => {
+                let steps = 100 / VOLUME_STEP as i32 + 1;
+                let curr = self.volume as i32 / VOLUME_STEP as i32;
+                self.volume = ((curr + delta).rem_euclid(steps) * VOLUME_STEP as i32) as u8;
+            }
+            SettingsEntry::Dithering => self.dithering = cycle(&DitherMode::ALL, self.dithering, delta),
+            SettingsEntry::Theme => self.theme = cycle(&Theme::ALL, self.theme, delta),
+            SettingsEntry::Shake => {
+                let steps = MAX_SHAKE as i32 / SHAKE_STEP as i32 + 1;
+                let curr = self.shake as i32 / SHAKE_STEP as i32;
+                self.shake = ((curr + delta).rem_euclid(steps) * SHAKE_STEP as i32) as u8;
+            }
+            SettingsEntry::FpsCap => self.fps_cap = cycle(&FpsCap::ALL, self.fps_cap, delta),
+            SettingsEntry::Fullscreen => self.fullscreen = !self.fullscreen,
+            SettingsEntry::ShowFps => self.show_fps = !self.show_fps,
+            SettingsEntry::Touch => self.touch = cycle(&TouchLayout::ALL, self.touch, delta),
+            SettingsEntry::Lives => self.lives = (self.lives as i32 - 1 + delta).rem_euclid(MAX_LIVES as i32) as u32 + 1,
+            SettingsEntry::Controls | SettingsEntry::Back => ()
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,268 @@
+/*
+Copyright (C) 2025 Amari Calipso
+
+This file is part of !pong.
+
+!pong is free software: you can redistribute it and/or modify
+it under the terms of the GNU Affero General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+!pong is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Affero General Public License for more details.
+
+You should have received a copy of the GNU Affero General Public License
+along with !pong.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use std::{collections::BTreeMap, ops::Range};
+
+use crate::{input_source::{AnyInputSource, InputFrame, InputSource}, obstacle::AnyObstacle, player::DeathCause, settings::Settings, world::{World, WorldEvent}, FrameInfo, REFERENCE_FRAMERATE, SIMULATION_MAX_FRAMES};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Csv, Json
+}
+
+impl OutputFormat {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "csv" => Some(OutputFormat::Csv),
+            "json" => Some(OutputFormat::Json),
+            _ => None
+        }
+    }
+}
+
+/// Obstacles on screen at a given moment, by kind
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ObstacleCounts {
+    pub rocks: u32,
+    /// Including missiles
+    pub rockets: u32,
+    pub mines: u32,
+    /// Including fragments
+    pub asteroids: u32,
+    pub barriers: u32
+}
+
+/// Statistics of a single headless game
+#[derive(Debug, Clone, Default)]
+pub struct GameStats {
+    pub seed: u64,
+    pub score: u64,
+    pub frames: u64,
+    /// `None` if the game was still going after `SIMULATION_MAX_FRAMES`
+    pub death_cause: Option<DeathCause>,
+
+    /// Pickups that appeared and were collected, by name
+    pub pickups_spawned: BTreeMap<&'static str, u32>,
+    pub pickups_collected: BTreeMap<&'static str, u32>,
+    pub bomb_points: u64,
+    pub obstacles_destroyed: u32,
+    pub lives_lost: u32,
+    pub rockets_launched: u32,
+
+    /// Obstacles on screen, sampled once per second
+    pub obstacle_counts: Vec<ObstacleCounts>
+}
+
+impl GameStats {
+    fn seconds(&self) -> f32 {
+        self.frames as f32 / REFERENCE_FRAMERATE
+    }
+
+    fn death_cause_name(&self) -> &'static str {
+        self.death_cause.map_or("none", DeathCause::name)
+    }
+
+    fn series(&self, f: impl Fn(&ObstacleCounts) -> u32) -> impl Iterator<Item = u32> {
+        self.obstacle_counts.iter().map(f)
+    }
+}
+
+/// Plays a game with the given policy until the player dies, at a fixed reference framerate
+pub fn simulate_game(seed: u64, lives: u32, mut policy: AnyInputSource) -> GameStats {
+    let settings = Settings::default();
+    let mut world = World::new(seed);
+    world.set_max_lives(lives);
+    let mut stats = GameStats { seed, ..Default::default() };
+
+    let sample_interval = REFERENCE_FRAMERATE as u64;
+
+    for frame in 0 .. SIMULATION_MAX_FRAMES {
+        let actions = policy.poll(&InputFrame {
+            rl: None,
+            settings: &settings,
+            frame,
+            game: world.view()
+        });
+
+        world.apply(&actions);
+        world.step(FrameInfo::new(1.0, true));
+
+        if world.player.playing {
+            stats.frames += 1;
+
+            if stats.frames.is_multiple_of(sample_interval) {
+                let count = |f: fn(&AnyObstacle) -> bool| world.obstacles.iter().filter(|x| f(x)).count() as u32;
+
+                stats.obstacle_counts.push(ObstacleCounts {
+                    rocks: count(|x| matches!(x, AnyObstacle::Rock(_))),
+                    rockets: count(|x| matches!(x, AnyObstacle::Rocket(_) | AnyObstacle::Missile(_))),
+                    mines: count(|x| matches!(x, AnyObstacle::Mine(_))),
+                    asteroids: count(|x| matches!(x, AnyObstacle::Asteroid(_))),
+                    barriers: count(|x| matches!(x, AnyObstacle::Barrier(_)))
+                });
+            }
+        }
+
+        for event in world.events.drain(..) {
+            match event {
+                WorldEvent::Death { cause, score } => {
+                    stats.score = score;
+                    stats.death_cause = Some(cause);
+                }
+                WorldEvent::PickupSpawned(name) => *stats.pickups_spawned.entry(name).or_insert(0) += 1,
+                WorldEvent::PickupCollected(name) => *stats.pickups_collected.entry(name).or_insert(0) += 1,
+                WorldEvent::BombPoints(points) => stats.bomb_points += points,
+                WorldEvent::ObstacleDestroyed => stats.obstacles_destroyed += 1,
+                WorldEvent::LifeLost(_) => stats.lives_lost += 1,
+                WorldEvent::RocketLaunched(_) => stats.rockets_launched += 1,
+                WorldEvent::Hit | WorldEvent::ShieldBroken | WorldEvent::RocketGone(_) => ()
+            }
+        }
+
+        if stats.death_cause.is_some() {
+            return stats;
+        }
+    }
+
+    stats.score = world.player.count;
+    stats
+}
+
+/// Runs `count` games with `lives` lives each, cycling through `seeds`
+pub fn simulate(count: u64, seeds: Range<u64>, lives: u32, policy: &AnyInputSource) -> Vec<GameStats> {
+    let span = seeds.end - seeds.start;
+
+    (0 .. count)
+        .map(|i| simulate_game(seeds.start + i % span, lives, policy.clone()))
+        .collect()
+}
+
+/// Aggregated statistics over a batch of games
+#[derive(Debug, Clone, Default)]
+pub struct Summary {
+    pub games: usize,
+    pub mean_score: f32,
+    pub median_score: u64,
+    pub p10_score: u64,
+    pub p90_score: u64,
+    pub mean_seconds: f32,
+    pub death_causes: BTreeMap<&'static str, u32>
+}
+
+impl Summary {
+    pub fn new(games: &[GameStats]) -> Self {
+        if games.is_empty() {
+            return Self::default();
+        }
+
+        let mut scores: Vec<u64> = games.iter().map(|x| x.score).collect();
+        scores.sort_unstable();
+
+        let percentile = |p: usize| scores[(scores.len() - 1) * p / 100];
+
+        let mut death_causes = BTreeMap::new();
+        for game in games {
+            *death_causes.entry(game.death_cause_name()).or_insert(0) += 1;
+        }
+
+        Self {
+            games: games.len(),
+            mean_score: scores.iter().sum::<u64>() as f32 / games.len() as f32,
+            median_score: percentile(50),
+            p10_score: percentile(10),
+            p90_score: percentile(90),
+            mean_seconds: games.iter().map(GameStats::seconds).sum::<f32>() / games.len() as f32,
+            death_causes
+        }
+    }
+
+    /// Short human readable report, meant for the terminal
+    pub fn report(&self) -> String {
+        let causes: Vec<String> = self.death_causes.iter()
+            .map(|(cause, count)| format!("{}: {}", cause, count))
+            .collect();
+
+        format!(
+            "games: {}\nscore: mean {:.2}, median {}, p10 {}, p90 {}\nmean survival: {:.2}s\ndeath causes: {}",
+            self.games, self.mean_score, self.median_score, self.p10_score, self.p90_score, self.mean_seconds, causes.join(", ")
+        )
+    }
+}
+
+fn join_counts(counts: &BTreeMap<&'static str, u32>, json: bool) -> String {
+    counts.iter()
+        .map(|(name, count)| if json { format!("\"{}\": {}", name, count) } else { format!("{}:{}", name, count) })
+        .collect::<Vec<_>>()
+        .join(if json { ", " } else { ";" })
+}
+
+fn join(values: impl Iterator<Item = u32>, separator: &str) -> String {
+    values.map(|x| x.to_string()).collect::<Vec<_>>().join(separator)
+}
+
+/// One row per game. Pickup counts and obstacle counts over time are `;` separated
+pub fn to_csv(games: &[GameStats]) -> String {
+    let mut out = String::from(
+        "seed,score,survival_frames,survival_seconds,death_cause,pickups_spawned,pickups_collected,bomb_points,obstacles_destroyed,lives_lost,rockets_launched,rocks_over_time,rockets_over_time,mines_over_time,asteroids_over_time,barriers_over_time\n"
+    );
+
+    for game in games {
+        out += &format!(
+            "{},{},{},{:.2},{},{},{},{},{},{},{},{},{},{},{},{}\n",
+            game.seed, game.score, game.frames, game.seconds(), game.death_cause_name(),
+            join_counts(&game.pickups_spawned, false), join_counts(&game.pickups_collected, false),
+            game.bomb_points, game.obstacles_destroyed, game.lives_lost, game.rockets_launched,
+            join(game.series(|x| x.rocks), ";"), join(game.series(|x| x.rockets), ";"),
+            join(game.series(|x| x.mines), ";"), join(game.series(|x| x.asteroids), ";"), join(game.series(|x| x.barriers), ";")
+        );
+    }
+
+    out
+}
+
+/// Every game, followed by the summary of the batch
+pub fn to_json(games: &[GameStats], summary: &Summary) -> String {
+    let games: Vec<String> = games.iter()
+        .map(|game| format!(
+            concat!(
+                "    {{\"seed\": {}, \"score\": {}, \"survival_frames\": {}, \"survival_seconds\": {:.2}, \"death_cause\": \"{}\", ",
+                "\"pickups_spawned\": {{{}}}, \"pickups_collected\": {{{}}}, \"bomb_points\": {}, \"obstacles_destroyed\": {}, \"lives_lost\": {}, \"rockets_launched\": {}, ",
+                "\"rocks_over_time\": [{}], \"rockets_over_time\": [{}], \"mines_over_time\": [{}], \"asteroids_over_time\": [{}], \"barriers_over_time\": [{}]}}"
+            ),
+            game.seed, game.score, game.frames, game.seconds(), game.death_cause_name(),
+            join_counts(&game.pickups_spawned, true), join_counts(&game.pickups_collected, true),
+            game.bomb_points, game.obstacles_destroyed, game.lives_lost, game.rockets_launched,
+            join(game.series(|x| x.rocks), ", "), join(game.series(|x| x.rockets), ", "),
+            join(game.series(|x| x.mines), ", "), join(game.series(|x| x.asteroids), ", "), join(game.series(|x| x.barriers), ", ")
+        ))
+        .collect();
+
+    let causes: Vec<String> = summary.death_causes.iter()
+        .map(|(cause, count)| format!("\"{}\": {}", cause, count))
+        .collect();
+
+    format!(
+        concat!(
+            "{{\n  \"games\": [\n{}\n  ],\n  \"summary\": {{\"games\": {}, \"mean_score\": {:.2}, \"median_score\": {}, \"p10_score\": {}, \"p90_score\": {}, ",
+            "\"mean_survival_seconds\": {:.2}, \"death_causes\": {{{}}}}}\n}}\n"
+        ),
+        games.join(",\n"), summary.games, summary.mean_score, summary.median_score, summary.p10_score, summary.p90_score,
+        summary.mean_seconds, causes.join(", ")
+    )
+}
\ No newline at end of file
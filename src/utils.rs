@@ -17,7 +17,9 @@ You should have received a copy of the GNU Affero General Public License
 along with !pong.  If not, see <http://www.gnu.org/licenses/>.
  */
 
-use raylib::math::Vector2;
+use raylib::math::{Rectangle, Vector2};
+
+use crate::INTERNAL_RESOLUTION;
 
 pub fn vec2(x: f32, y: f32) -> Vector2 {
     Vector2 { x, y }
@@ -42,4 +44,33 @@ pub fn square_collides(pos: Vector2, size: Vector2, other_pos: Vector2, other_si
     let xr = pos.x .. pos.x + size.x;
     (xr.contains(&other_pos.x) || xr.contains(&(other_pos.x + other_size.x))) &&
     (yr.contains(&other_pos.y) || yr.contains(&(other_pos.y + other_size.y)))
+}
+
+/// Unlike `square_collides`, which only looks at the other object's corners, also works when it's wider or taller than `size`,
+/// like thin objects lying across another one
+pub fn rects_overlap(pos: Vector2, size: Vector2, other_pos: Vector2, other_size: Vector2) -> bool {
+    pos.x < other_pos.x + other_size.x && other_pos.x < pos.x + size.x &&
+    pos.y < other_pos.y + other_size.y && other_pos.y < pos.y + size.y
+}
+
+pub fn circle_overlaps_rect(center: Vector2, radius: f32, pos: Vector2, size: Vector2) -> bool {
+    let closest = Vector2 {
+        x: center.x.clamp(pos.x, pos.x + size.x),
+        y: center.y.clamp(pos.y, pos.y + size.y)
+    };
+
+    closest.distance_to(center) < radius
+}
+
+pub fn point_in_rect(point: Vector2, rect: Rectangle) -> bool {
+    (rect.x .. rect.x + rect.width).contains(&point.x) &&
+    (rect.y .. rect.y + rect.height).contains(&point.y)
+}
+
+/// Maps a position in window coordinates to the internal resolution, given the area of the window the game is drawn into
+pub fn to_internal(pos: Vector2, screen: Rectangle) -> Vector2 {
+    Vector2 {
+        x: (pos.x - screen.x) * INTERNAL_RESOLUTION.x / screen.width,
+        y: (pos.y - screen.y) * INTERNAL_RESOLUTION.y / screen.height
+    }
 }
\ No newline at end of file
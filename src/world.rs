@@ -0,0 +1,785 @@
+/*
+Copyright (C) 2025 Amari Calipso
+
+This file is part of !pong.
+
+!pong is free software: you can redistribute it and/or modify
+it under the terms of the GNU Affero General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+!pong is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Affero General Public License for more details.
+
+You should have received a copy of the GNU Affero General Public License
+along with !pong.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use std::f32::consts::{FRAC_PI_2, PI};
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use raylib::{math::Vector2, prelude::RaylibDraw};
+
+use crate::{explosion::Explosion, input::{Action, Actions}, input_source::GameView, obstacle::{asteroid::Asteroid, barrier::Barrier, explosion::ObstacleExplosion, gravity_well::{total_force, Field, GravityWell}, laser::Laser, mine::Mine, missile::Missile, rock::Rock, rocket::{self, Rocket}, AnyObstacle, Obstacle, Shape}, obstacle_grid::ObstacleGrid, pad::Pad, pickup::{is_blinking, AnyPickup, Effects, Pickup, PickupContext, SPAWN_RULES}, player::{DeathCause, Player}, utils::vec2, FrameInfo, ASTEROID_PROBABILITY, BARRIER_PROBABILITY, FG, GRAVITY_WELL_PROBABILITY, GRAVITY_WELL_SAFE_DISTANCE, HIT_COOLDOWN, INTERNAL_RESOLUTION, LASER_PROBABILITY, LASER_SAFE_DISTANCE, LASER_WIDTH, MAGNET_RADIUS, MAX_OBSTACLE_SIZE, MINE_BLAST_RADIUS, MINE_PROBABILITY, MISSILE_PROBABILITY, MISSILE_SAFE_DISTANCE, MISSILE_TRICK_POINTS, MOD_INCREMENT_DIFF, NOCLIP, OBSTACLE_COLLISION_MAX_VELOCITY, OBSTACLE_PROBABILITY, OBSTACLE_SAFE_ZONE, PAD_SIZE, PLAYER_COUNT_ASTEROID, PLAYER_COUNT_BARRIER, PLAYER_COUNT_GRAVITY_WELL, PLAYER_COUNT_LASER, PLAYER_COUNT_MINE, PLAYER_COUNT_MISSILE, PLAYER_COUNT_OBST, PLAYER_SIZE, RAINBOW_SMASH_POINTS, RESPAWN_CLEAR_RADIUS, ROCKETS, ROCKETS_TEST, ROCKET_LANE_MARGIN, ROCKET_LAUNCH_ATTEMPTS, ROCKET_SAFE_DISTANCE, SPRINT_CHARGE_DELTA, SPRINT_COOLDOWN, SPRINT_MAX_VALUE, SPRINT_USE_DELTA, START_DIFFICULTY};
+
+/// Something that happened during a step of the simulation, for the frontend to play sounds or collect statistics
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorldEvent {
+    /// The player bounced off a pad
+    Hit,
+    /// The player's shield absorbed a hit
+    ShieldBroken,
+    /// The player died with the given score
+    Death { cause: DeathCause, score: u64 },
+    /// The player lost a life and respawned, keeping its score
+    LifeLost(DeathCause),
+    /// A pickup with the given name appeared
+    PickupSpawned(&'static str),
+    /// The player collected a pickup with the given name
+    PickupCollected(&'static str),
+    /// A bomb that found nothing to destroy turned into points
+    BombPoints(u64),
+    /// An obstacle was destroyed by a pickup, by the player or by another obstacle
+    ObstacleDestroyed,
+    RocketLaunched(u16),
+    /// The rocket or missile with the given ID left the screen or was destroyed
+    RocketGone(u16)
+}
+
+/// Everything that's simulated, independently from rendering, sound and menus
+#[derive(Debug)]
+pub struct World {
+    pub player: Player,
+
+    pub left_pad:  Pad,
+    pub right_pad: Pad,
+
+    pub obstacles: Vec<AnyObstacle>,
+    obstacle_grid: ObstacleGrid,
+    pub pickups: Vec<AnyPickup>,
+    pub effects: Effects,
+
+    difficulty: u16,
+    last_player_count: u64,
+
+    /// Pad hits left before the pads shrink back to their normal size
+    custom_pad_count: u32,
+    /// Lives the player starts every game with
+    max_lives: u32,
+    pub lives: u32,
+    pub sprint_amount: f32,
+
+    sprint_cooldown: f32,
+    hit_cooldown: f32,
+
+    rng: StdRng,
+
+    curr_rocket_id: u16,
+
+    /// Events of the last steps, to be drained by the frontend
+    pub events: Vec<WorldEvent>
+}
+
+impl World {
+    pub fn new(seed: u64) -> Self {
+        let mut world = Self {
+            left_pad: Pad::default(true),
+            right_pad: Pad::default(false),
+            player: Player::new(),
+            obstacles: Vec::new(),
+            obstacle_grid: ObstacleGrid::new(),
+            pickups: Vec::new(),
+            effects: Effects::default(),
+            difficulty: START_DIFFICULTY,
+            last_player_count: 0,
+            custom_pad_count: 0,
+            max_lives: 1,
+            lives: 1,
+            sprint_amount: 0.0,
+            sprint_cooldown: SPRINT_COOLDOWN,
+            hit_cooldown: HIT_COOLDOWN,
+            rng: StdRng::seed_from_u64(seed),
+            curr_rocket_id: 0,
+            events: Vec::new()
+        };
+
+        world.player.init(&mut world.rng);
+        world
+    }
+
+    pub fn view(&self) -> GameView<'_> {
+        GameView {
+            player: &self.player,
+            left_pad: &self.left_pad,
+            right_pad: &self.right_pad,
+            obstacles: &self.obstacles,
+            sprint_amount: self.sprint_amount,
+            sprint_cooldown: self.sprint_cooldown,
+            obstacle_time_scale: self.effects.obstacle_time_scale()
+        }
+    }
+
+    fn reset_part(&mut self) {
+        self.curr_rocket_id = 0;
+        self.difficulty = START_DIFFICULTY;
+        self.last_player_count = 0;
+        self.obstacles.clear();
+        self.pickups.clear();
+        self.effects = Effects::default();
+        self.custom_pad_count = 0;
+        self.lives = self.max_lives;
+        self.sprint_amount = 0.0;
+        self.sprint_cooldown = SPRINT_COOLDOWN;
+        self.hit_cooldown = HIT_COOLDOWN;
+        self.obstacle_grid.reset();
+    }
+
+    pub fn sprint_off(&mut self) {
+        self.player.sprint_off();
+    }
+
+    fn sprint_on(&mut self) {
+        if self.sprint_amount > 0.0 && self.sprint_cooldown >= SPRINT_COOLDOWN {
+            self.sprint_cooldown = 0.0;
+            self.player.sprint_on();
+        }
+    }
+
+    fn reset(&mut self) {
+        self.reset_part();
+        self.left_pad.reset();
+        self.right_pad.reset();
+        self.sprint_off();
+    }
+
+    /// Goes back to the title screen's state, with the player hovering
+    pub fn quit(&mut self) {
+        self.reset();
+        self.player.reset(&mut self.rng);
+    }
+
+    /// Starts a new game right away
+    pub fn restart(&mut self) {
+        self.quit();
+        self.player.jump(&mut self.rng);
+    }
+
+    fn destroy_obstacle(&mut self, i: usize) {
+        self.events.push(WorldEvent::ObstacleDestroyed);
+        self.obstacles[i].kill();
+        let mut explosion = Explosion::new(self.obstacles[i].pos());
+        explosion.explode(OBSTACLE_COLLISION_MAX_VELOCITY, true, &mut self.rng);
+        self.obstacles.push(ObstacleExplosion(explosion).into());
+    }
+
+    /// Breaks the asteroid at `i` into fragments, if there's one and it wasn't destroyed already
+    fn split_asteroid(&mut self, i: usize) {
+        if let AnyObstacle::Asteroid(asteroid) = &self.obstacles[i] && asteroid.is_alive() {
+            let fragments = asteroid.split(&mut self.rng);
+            self.obstacles.extend(fragments.into_iter().map(AnyObstacle::from));
+        }
+    }
+
+    /// Destroys the obstacles caught in the blast of the mine at `i`. Returns whether the player got killed by it
+    fn blast(&mut self, i: usize, center: Vector2) -> bool {
+        let area = Shape::Circle { center, radius: MINE_BLAST_RADIUS };
+
+        for j in 0 .. self.obstacles.len() {
+            let obstacle = &self.obstacles[j];
+
+            if j != i && obstacle.can_collide() && obstacle.hits_obstacles() && area.overlaps(&obstacle.shape()) {
+                self.destroy_obstacle(j);
+            }
+        }
+
+        if NOCLIP || self.player.is_invulnerable() || self.player.is_rainbow() || !area.overlaps_rect(self.player.pos, vec2(PLAYER_SIZE, PLAYER_SIZE)) {
+            return false;
+        }
+
+        if self.player.shielded {
+            self.player.shielded = false;
+            self.events.push(WorldEvent::ShieldBroken);
+            return false;
+        }
+
+        self.player.dead = Some(DeathCause::Mine);
+        true
+    }
+
+    pub fn max_lives(&self) -> u32 {
+        self.max_lives
+    }
+
+    /// Sets the lives of every game, starting from the current one unless it's already being played
+    pub fn set_max_lives(&mut self, lives: u32) {
+        self.max_lives = lives;
+
+        if !self.player.playing {
+            self.lives = lives;
+        }
+    }
+
+    /// Puts the player back at the center, destroying the obstacles around it
+    fn respawn(&mut self) {
+        self.player.respawn(&mut self.rng);
+
+        let center = self.player.pos + Vector2::one() * (PLAYER_SIZE / 2.0);
+        for i in 0 .. self.obstacles.len() {
+            let obstacle = &self.obstacles[i];
+
+            if obstacle.can_collide() && (obstacle.pos() + obstacle.size() / 2.0).distance_to(center) <= RESPAWN_CLEAR_RADIUS {
+                self.destroy_obstacle(i);
+            }
+        }
+    }
+
+    fn invert(&mut self) {
+        if self.hit_cooldown >= HIT_COOLDOWN {
+            self.hit_cooldown = 0.0;
+            self.events.push(WorldEvent::Hit);
+            self.player.invert();
+
+            if self.custom_pad_count > 0 {
+                self.custom_pad_count -= 1;
+
+                if self.custom_pad_count == 0 {
+                    self.left_pad.resize(PAD_SIZE.y);
+                    self.right_pad.resize(PAD_SIZE.y);
+                }
+            }
+        }
+    }
+
+    /// Applies the gameplay actions of a frame. Jumping also starts the game, if it wasn't started yet
+    pub fn apply(&mut self, actions: &Actions) {
+        if !self.player.sprinting {
+            if actions.get(Action::Sprint).pressed {
+                if self.player.playing {
+                    self.sprint_on();
+                }
+            } else if actions.get(Action::Jump).pressed {
+                self.player.jump(&mut self.rng);
+            }
+
+            return;
+        }
+
+        if self.player.playing && self.player.sprinting && actions.get(Action::Sprint).released {
+            self.sprint_off();
+        }
+    }
+
+    fn rocket_launch(&mut self) -> (rocket::Base, Vector2) {
+        let base = rocket::Base::random(&mut self.rng);
+
+        let pos = {
+            match base {
+                rocket::Base::Left => {
+                    if self.rng.random_bool(0.5) && OBSTACLE_SAFE_ZONE.y < self.left_pad.pos.y {
+                        Vector2 {
+                            x: 0.0,
+                            y: self.rng.random_range(OBSTACLE_SAFE_ZONE.y ..= self.left_pad.pos.y)
+                        }
+                    } else {
+                        Vector2 {
+                            x: 0.0,
+                            y: self.rng.random_range(self.left_pad.pos.y + self.left_pad.size().y ..= INTERNAL_RESOLUTION.y - OBSTACLE_SAFE_ZONE.y)
+                        }
+                    }
+                }
+                rocket::Base::Right => {
+                    if self.rng.random_bool(0.5) && OBSTACLE_SAFE_ZONE.y < self.right_pad.pos.y {
+                        Vector2 {
+                            x: INTERNAL_RESOLUTION.x,
+                            y: self.rng.random_range(OBSTACLE_SAFE_ZONE.y ..= self.right_pad.pos.y)
+                        }
+                    } else {
+                        Vector2 {
+                            x: INTERNAL_RESOLUTION.x,
+                            y: self.rng.random_range(self.right_pad.pos.y + self.right_pad.size().y ..= INTERNAL_RESOLUTION.y - OBSTACLE_SAFE_ZONE.y)
+                        }
+                    }
+                }
+                rocket::Base::Top => {
+                    Vector2 {
+                        x: self.rng.random_range(OBSTACLE_SAFE_ZONE.x ..= INTERNAL_RESOLUTION.x - OBSTACLE_SAFE_ZONE.x),
+                        y: 0.0
+                    }
+                }
+                rocket::Base::Bottom => {
+                    Vector2 {
+                        x: self.rng.random_range(OBSTACLE_SAFE_ZONE.x ..= INTERNAL_RESOLUTION.x - OBSTACLE_SAFE_ZONE.x),
+                        y: INTERNAL_RESOLUTION.y
+                    }
+                }
+            }
+        };
+
+        (base, pos)
+    }
+
+    /// Whether a rocket launched from `pos` would fly straight into a player too close to the edge to react
+    fn aims_at_player(&self, base: &rocket::Base, pos: Vector2) -> bool {
+        let player = self.player.pos;
+        let in_lane = |player: f32, rocket: f32| {
+            rocket - ROCKET_LANE_MARGIN < player + PLAYER_SIZE && player < rocket + MAX_OBSTACLE_SIZE + ROCKET_LANE_MARGIN
+        };
+
+        match base {
+            rocket::Base::Left => in_lane(player.y, pos.y) && player.x < ROCKET_SAFE_DISTANCE,
+            rocket::Base::Right => in_lane(player.y, pos.y) && INTERNAL_RESOLUTION.x - player.x - PLAYER_SIZE < ROCKET_SAFE_DISTANCE,
+            rocket::Base::Top => in_lane(player.x, pos.x) && player.y < ROCKET_SAFE_DISTANCE,
+            rocket::Base::Bottom => in_lane(player.x, pos.x) && INTERNAL_RESOLUTION.y - player.y - PLAYER_SIZE < ROCKET_SAFE_DISTANCE
+        }
+    }
+
+    /// Returns `None` if no fair launch was found
+    fn new_rocket(&mut self) -> Option<Rocket> {
+        for _ in 0 .. ROCKET_LAUNCH_ATTEMPTS {
+            let (base, pos) = self.rocket_launch();
+            if self.aims_at_player(&base, pos) {
+                continue;
+            }
+
+            let id = self.curr_rocket_id;
+            self.curr_rocket_id = self.curr_rocket_id.wrapping_add(1);
+            return Some(Rocket::new(&mut self.rng, id, pos, base));
+        }
+
+        None
+    }
+
+    fn make_rocket(&mut self) {
+        if let Some(rocket) = self.new_rocket() {
+            self.events.push(WorldEvent::RocketLaunched(rocket.id));
+            self.obstacles.push(rocket.into());
+        }
+    }
+
+    /// Launches a missile from an edge, pointing inward, if a spot far enough from the player is found
+    fn make_missile(&mut self) {
+        for _ in 0 .. ROCKET_LAUNCH_ATTEMPTS {
+            let (base, pos) = self.rocket_launch();
+            if pos.distance_to(self.player.pos) < MISSILE_SAFE_DISTANCE {
+                continue;
+            }
+
+            let angle = match base {
+                rocket::Base::Left => 0.0,
+                rocket::Base::Right => PI,
+                rocket::Base::Top => FRAC_PI_2,
+                rocket::Base::Bottom => -FRAC_PI_2
+            };
+
+            let id = self.curr_rocket_id;
+            self.curr_rocket_id = self.curr_rocket_id.wrapping_add(1);
+            self.events.push(WorldEvent::RocketLaunched(id));
+            self.obstacles.push(Missile::new(id, pos, angle).into());
+            return;
+        }
+    }
+
+    /// Opens a gravity well away from the player and the pads. Only one can be around at a time
+    fn make_gravity_well(&mut self) {
+        if self.obstacles.iter().any(|x| matches!(x, AnyObstacle::GravityWell(_))) {
+            return;
+        }
+
+        for _ in 0 .. ROCKET_LAUNCH_ATTEMPTS {
+            let center = Vector2 {
+                x: self.rng.random_range(OBSTACLE_SAFE_ZONE.x ..= INTERNAL_RESOLUTION.x - OBSTACLE_SAFE_ZONE.x),
+                y: self.rng.random_range(OBSTACLE_SAFE_ZONE.y ..= INTERNAL_RESOLUTION.y - OBSTACLE_SAFE_ZONE.y)
+            };
+
+            if center.distance_to(self.player.pos) < GRAVITY_WELL_SAFE_DISTANCE {
+                continue;
+            }
+
+            self.obstacles.push(GravityWell::new(center).into());
+            return;
+        }
+    }
+
+    /// Places a barrier between two free cells, away from the player's path
+    fn make_barrier(&mut self) {
+        let path = self.player.predict_path(&self.left_pad, &self.right_pad);
+
+        if let Some((id, start, end)) = self.obstacle_grid.alloc_pair(&path, &mut self.rng) {
+            self.obstacles.push(Barrier::new(id, start, end).into());
+        }
+    }
+
+    /// Announces a beam across the arena, away from the player. Only one can be around at a time
+    fn make_laser(&mut self) {
+        if self.obstacles.iter().any(|x| matches!(x, AnyObstacle::Laser(_))) {
+            return;
+        }
+
+        for _ in 0 .. ROCKET_LAUNCH_ATTEMPTS {
+            let horizontal = self.rng.random_bool(0.5);
+            let (offset, player) = {
+                if horizontal {
+                    (self.rng.random_range(OBSTACLE_SAFE_ZONE.y ..= INTERNAL_RESOLUTION.y - OBSTACLE_SAFE_ZONE.y - LASER_WIDTH), self.player.pos.y)
+                } else {
+                    (self.rng.random_range(OBSTACLE_SAFE_ZONE.x ..= INTERNAL_RESOLUTION.x - OBSTACLE_SAFE_ZONE.x - LASER_WIDTH), self.player.pos.x)
+                }
+            };
+
+            if (offset + LASER_WIDTH / 2.0 - player - PLAYER_SIZE / 2.0).abs() < LASER_SAFE_DISTANCE {
+                continue;
+            }
+
+            self.obstacles.push(Laser::new(horizontal, offset).into());
+            return;
+        }
+    }
+
+    fn spawn_pickup(&mut self, frame_info: FrameInfo) {
+        for rule in SPAWN_RULES {
+            if self.player.count < rule.min_score {
+                continue;
+            }
+
+            let probability = (rule.probability as f32 / frame_info.clamped_delta_time).round() as u16;
+            if self.rng.random_range(0..=probability) >= 1 {
+                continue;
+            }
+
+            let path = self.player.predict_path(&self.left_pad, &self.right_pad);
+            let obstacles: Vec<(Vector2, Vector2)> = self.obstacles.iter()
+                .filter(|x| x.can_collide())
+                .map(|x| (x.pos(), x.size()))
+                .collect();
+
+            if let Some(pos) = self.obstacle_grid.pickup_spot(&path, &obstacles, rule.size, &mut self.rng) {
+                let pickup = (rule.spawn)(pos);
+                self.events.push(WorldEvent::PickupSpawned(pickup.name()));
+                self.pickups.push(pickup);
+            }
+
+            return;
+        }
+    }
+
+    /// Advances the simulation by one frame
+    pub fn step(&mut self, frame_info: FrameInfo) {
+        let score = self.player.count;
+        if let Some(cause) = self.player.check_death(&self.left_pad, &self.right_pad, frame_info.tolerance, &mut self.rng) {
+            if self.lives > 1 {
+                self.lives -= 1;
+                self.events.push(WorldEvent::LifeLost(cause));
+                self.respawn();
+            } else {
+                self.player.reset(&mut self.rng);
+                self.events.push(WorldEvent::Death { cause, score });
+                self.reset();
+            }
+        }
+
+        if self.player.playing {
+            self.left_pad.update(frame_info.delta_time);
+            self.right_pad.update(frame_info.delta_time);
+
+            if self.left_pad.move_if_collides(self.player.pos, frame_info.tolerance, &mut self.rng) ||
+               self.right_pad.move_if_collides(self.player.pos, frame_info.tolerance, &mut self.rng)
+            {
+                self.invert();
+            }
+
+            let had_pickups = !self.pickups.is_empty();
+
+            let mut ctx = PickupContext {
+                player: &mut self.player,
+                left_pad: &mut self.left_pad,
+                right_pad: &mut self.right_pad,
+                custom_pad_count: &mut self.custom_pad_count,
+                effects: &mut self.effects,
+                obstacles: &mut self.obstacles,
+                events: &mut self.events,
+                rng: &mut self.rng
+            };
+
+            self.pickups.retain_mut(|pickup| {
+                if !pickup.is_alive() {
+                    pickup.expire(&mut ctx);
+                    return false;
+                }
+
+                pickup.update(frame_info, &mut ctx);
+
+                if ctx.effects.magnet() && pickup.can_collect() {
+                    let player_center = ctx.player.pos + Vector2::one() * (PLAYER_SIZE / 2.0);
+                    let center = pickup.pos() + pickup.size() / 2.0;
+
+                    if center.distance_to(player_center) <= MAGNET_RADIUS {
+                        pickup.attract(player_center - pickup.size() / 2.0);
+                    }
+                }
+
+                if pickup.can_collect() && pickup.collides(ctx.player.pos, vec2(PLAYER_SIZE, PLAYER_SIZE)) {
+                    ctx.events.push(WorldEvent::PickupCollected(pickup.name()));
+                    pickup.collect(&mut ctx);
+                }
+
+                true
+            });
+
+            if !had_pickups && frame_info.in_reference_frame {
+                self.spawn_pickup(frame_info);
+            }
+
+            if self.player.count >= PLAYER_COUNT_OBST {
+                if self.player.count != self.last_player_count && self.player.count % MOD_INCREMENT_DIFF == 0 {
+                    self.last_player_count = self.player.count;
+                    self.difficulty += 1;
+                }
+
+                if frame_info.in_reference_frame {
+                    if ROCKETS_TEST {
+                        self.make_rocket();
+                    } else {
+                        let probability = (OBSTACLE_PROBABILITY as f32 / frame_info.clamped_delta_time).round() as u16;
+                        if self.rng.random_bool(0.5) {
+                            if self.rng.random_range(0..=probability) < self.difficulty {
+                                if self.player.count >= PLAYER_COUNT_GRAVITY_WELL && self.rng.random_range(0 .. GRAVITY_WELL_PROBABILITY) == 0 {
+                                    self.make_gravity_well();
+                                } else if self.player.count >= PLAYER_COUNT_BARRIER && self.rng.random_range(0 .. BARRIER_PROBABILITY) == 0 {
+                                    self.make_barrier();
+                                } else {
+                                    let path = self.player.predict_path(&self.left_pad, &self.right_pad);
+                                    if let Some((id, pos)) = self.obstacle_grid.alloc(&path, &mut self.rng) {
+                                        if self.player.count >= PLAYER_COUNT_MINE && self.rng.random_range(0 .. MINE_PROBABILITY) == 0 {
+                                            self.obstacles.push(Mine::new(id, pos).into());
+                                        } else if self.player.count >= PLAYER_COUNT_ASTEROID && self.rng.random_range(0 .. ASTEROID_PROBABILITY) == 0 {
+                                            self.obstacles.push(Asteroid::new(&mut self.rng, id, pos).into());
+                                        } else {
+                                            self.obstacles.push(Rock::new(&mut self.rng, id, pos).into());
+                                        }
+                                    } else if ROCKETS { // if you can't allocate a rock, make a rocket instead
+                                        self.make_rocket();
+                                    }
+                                }
+                            }
+                        } else {
+                            if ROCKETS {
+                                if self.rng.random_range(0..=probability) < self.difficulty {
+                                    if self.player.count >= PLAYER_COUNT_MISSILE && self.rng.random_range(0 .. MISSILE_PROBABILITY) == 0 {
+                                        self.make_missile();
+                                    } else if self.player.count >= PLAYER_COUNT_LASER && self.rng.random_range(0 .. LASER_PROBABILITY) == 0 {
+                                        self.make_laser();
+                                    } else {
+                                        self.make_rocket();
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            let player_center = self.player.pos + Vector2::one() * (PLAYER_SIZE / 2.0);
+
+            let fields: Vec<Field> = self.obstacles.iter()
+                .filter_map(|x| if let AnyObstacle::GravityWell(well) = x { well.field() } else { None })
+                .collect();
+
+            self.player.pull(total_force(&fields, player_center));
+            if !fields.is_empty() && frame_info.in_reference_frame {
+                for obstacle in self.obstacles.iter_mut() {
+                    obstacle.pull(&fields);
+                }
+
+                self.player.explosion.pull(&fields);
+            }
+
+            let obstacle_frame_info = frame_info.scaled(self.effects.obstacle_time_scale());
+            for i in 0 .. self.obstacles.len() {
+                match &mut self.obstacles[i] {
+                    AnyObstacle::Barrier(barrier) => barrier.occupy(&mut self.obstacle_grid),
+                    AnyObstacle::Asteroid(asteroid) => asteroid.leave_cell(&mut self.obstacle_grid),
+                    _ => ()
+                }
+
+                self.obstacles[i].steer(player_center);
+                self.obstacles[i].update(obstacle_frame_info, &mut self.rng);
+
+                if let AnyObstacle::Mine(mine) = &mut self.obstacles[i] && let Some(center) = mine.take_blast() {
+                    self.events.push(WorldEvent::ObstacleDestroyed);
+                    if self.blast(i, center) {
+                        break;
+                    }
+                }
+
+                if !self.obstacles[i].can_collide() {
+                    continue;
+                }
+
+                // fragments get some time to fly out of the bomb that split them
+                let fresh = matches!(&self.obstacles[i], AnyObstacle::Asteroid(asteroid) if asteroid.is_fresh());
+                let destroyed = !fresh && self.pickups.iter()
+                    .filter(|pickup| pickup.destroys_obstacles())
+                    .any(|pickup| self.obstacles[i].collides_object(pickup.pos(), pickup.size()));
+
+                if destroyed {
+                    self.split_asteroid(i);
+                    self.destroy_obstacle(i);
+                    continue;
+                }
+
+                if !NOCLIP && !self.player.is_invulnerable() {
+                    if self.obstacles[i].collides_object(self.player.pos, vec2(PLAYER_SIZE, PLAYER_SIZE)) {
+                        // other hazards don't hurt while invincible, but can't be smashed either
+                        if self.player.is_rainbow() {
+                            if matches!(self.obstacles[i], AnyObstacle::Rock(_) | AnyObstacle::Asteroid(_) | AnyObstacle::Rocket(_) | AnyObstacle::Missile(_)) {
+                                self.player.count += RAINBOW_SMASH_POINTS;
+                                self.destroy_obstacle(i);
+                            }
+
+                            continue;
+                        }
+
+                        if self.player.shielded {
+                            self.player.shielded = false;
+                            self.events.push(WorldEvent::ShieldBroken);
+                            self.destroy_obstacle(i);
+                            continue;
+                        }
+
+                        let cause = {
+                            match self.obstacles[i] {
+                                AnyObstacle::Rocket(_) => DeathCause::Rocket,
+                                AnyObstacle::Missile(_) => DeathCause::Missile,
+                                AnyObstacle::Laser(_) => DeathCause::Laser,
+                                AnyObstacle::GravityWell(_) => DeathCause::GravityWell,
+                                AnyObstacle::Mine(_) => DeathCause::Mine,
+                                AnyObstacle::Asteroid(_) => DeathCause::Asteroid,
+                                AnyObstacle::Barrier(_) => DeathCause::Barrier,
+                                _ => DeathCause::Rock
+                            }
+                        };
+
+                        self.player.dead = Some(cause);
+                        break;
+                    }
+                }
+
+                for j in 0 .. self.obstacles.len() {
+                    if j == i || !self.obstacles[j].can_collide() || !self.obstacles[i].hits_obstacles() || !self.obstacles[j].hits_obstacles() {
+                        continue;
+                    }
+
+                    let collides = {
+                        if i < j {
+                            let (l, r) = self.obstacles.split_at_mut(j);
+                            l[i].collides_other(&r[0])
+                        } else {
+                            let (l, r) = self.obstacles.split_at_mut(i);
+                            l[j].collides_other(&r[0])
+                        }
+                    };
+
+                    if collides {
+                        // pairs are checked from both sides, so only the first collision counts
+                        let tricked = [i, j].into_iter()
+                            .any(|k| matches!(self.obstacles[k], AnyObstacle::Missile(_)) && self.obstacles[k].is_alive());
+
+                        if tricked {
+                            self.player.count += MISSILE_TRICK_POINTS;
+                        }
+
+                        for (k, other) in [(i, j), (j, i)] {
+                            if matches!(self.obstacles[other], AnyObstacle::Rocket(_) | AnyObstacle::Missile(_)) {
+                                self.split_asteroid(k);
+                            }
+                        }
+
+                        self.events.push(WorldEvent::ObstacleDestroyed);
+                        self.obstacles[i].kill();
+                        self.obstacles[j].kill();
+                        let mut explosion = Explosion::new(self.obstacles[i].pos());
+                        explosion.explode(OBSTACLE_COLLISION_MAX_VELOCITY, true, &mut self.rng);
+                        self.obstacles.push(ObstacleExplosion(explosion).into());
+                    }
+                }
+            }
+
+            self.obstacles.retain_mut(|obstacle| {
+                if obstacle.is_alive() {
+                    return true;
+                }
+
+                match obstacle {
+                    AnyObstacle::Rock(rock) => {
+                        self.obstacle_grid.free(rock.id).expect("Same ID was freed twice");
+                    }
+                    AnyObstacle::Mine(mine) => {
+                        self.obstacle_grid.free(mine.id).expect("Same ID was freed twice");
+                    }
+                    AnyObstacle::Barrier(barrier) => {
+                        for id in barrier.cells() {
+                            self.obstacle_grid.free(id).expect("Same ID was freed twice");
+                        }
+                    }
+                    AnyObstacle::Asteroid(asteroid) => {
+                        if let Some(id) = asteroid.id {
+                            self.obstacle_grid.free(id).expect("Same ID was freed twice");
+                        }
+                    }
+                    AnyObstacle::Rocket(rocket) => {
+                        self.events.push(WorldEvent::RocketGone(rocket.id));
+                    }
+                    AnyObstacle::Missile(missile) => {
+                        self.events.push(WorldEvent::RocketGone(missile.id));
+                    }
+                    _ => ()
+                }
+
+                false
+            });
+
+            if self.player.sprinting {
+                if self.sprint_amount > 0.0 {
+                    self.sprint_amount -= SPRINT_USE_DELTA * frame_info.delta_time;
+                } else {
+                    self.sprint_off();
+                }
+            } else {
+                if self.sprint_amount < SPRINT_MAX_VALUE {
+                    self.sprint_amount += SPRINT_CHARGE_DELTA * frame_info.delta_time;
+                }
+            }
+
+            self.effects.update(frame_info.delta_time);
+            self.sprint_cooldown += frame_info.delta_time;
+            self.hit_cooldown += frame_info.delta_time;
+        }
+
+        self.player.update(frame_info);
+    }
+
+    pub fn draw(&self, draw: &mut impl RaylibDraw) {
+        if self.player.playing {
+            self.left_pad.draw(draw);
+            self.right_pad.draw(draw);
+
+            for pickup in &self.pickups {
+                pickup.draw(draw);
+            }
+
+            for obstacle in &self.obstacles {
+                obstacle.draw(draw);
+            }
+        }
+
+        if self.player.playing && self.effects.magnet() && !is_blinking(self.effects.magnet) {
+            draw.draw_circle_lines(
+                (self.player.pos.x + PLAYER_SIZE / 2.0) as i32, (self.player.pos.y + PLAYER_SIZE / 2.0) as i32, 
+                MAGNET_RADIUS, FG
+            );
+        }
+
+        self.player.draw(draw);
+    }
+}
\ No newline at end of file